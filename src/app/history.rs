@@ -0,0 +1,53 @@
+use std::collections::VecDeque;
+
+const HISTORY_DEPTH: usize = 50;
+
+// commands entered this session, recalled with up and down in
+// the command bar, `cursor` is the entry on display, if any
+#[derive(Default)]
+pub struct CommandHistory {
+    entries: VecDeque<String>,
+    cursor: Option<usize>,
+}
+
+impl CommandHistory {
+    pub fn push(&mut self, command: &str) {
+        self.cursor = None;
+        let command = command.trim();
+        if command.is_empty() || self.entries.back().map(|c| c.as_str()) == Some(command) {
+            return;
+        }
+        if self.entries.len() == HISTORY_DEPTH {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(command.to_owned());
+    }
+
+    pub fn reset(&mut self) {
+        self.cursor = None;
+    }
+
+    // the next older command, sticking to the oldest one
+    pub fn prev(&mut self) -> Option<String> {
+        let pos = match self.cursor {
+            Some(0) => 0,
+            Some(p) => p - 1,
+            None => self.entries.len().checked_sub(1)?,
+        };
+        self.cursor = Some(pos);
+        self.entries.get(pos).cloned()
+    }
+
+    // the next newer command, walking past the newest one
+    // leaves an empty command bar
+    pub fn next(&mut self) -> Option<String> {
+        let pos = self.cursor? + 1;
+        if pos < self.entries.len() {
+            self.cursor = Some(pos);
+            self.entries.get(pos).cloned()
+        } else {
+            self.cursor = None;
+            Some(String::new())
+        }
+    }
+}
@@ -1,51 +1,970 @@
+use std::collections::{HashMap, HashSet};
 use std::default::Default;
 use std::f64;
-use std::fs::{File, OpenOptions};
+use std::fs::{self, File};
 use std::io::prelude::*;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::mpsc::channel;
-use std::time::Duration;
+use std::time::Instant;
 
-use chrono::Local;
+use chrono::{Local, NaiveDate};
 use cursive::direction::Absolute;
+use cursive::event::{Event, Key};
 use cursive::Vec2;
 use notify::{watcher, RecursiveMode, Watcher};
 
-use crate::command::{Command, CommandLineError};
-use crate::habit::{Bit, Count, HabitWrapper, TrackEvent, ViewMode};
+use crate::command::{resolve_habit, Command, CommandLineError, Comparison, Resolution, SortKey};
+use crate::export;
+use crate::habit::{
+    Bit, Capped, Checklist, Count, Duration, FloatCount, HabitWrapper, TrackEvent, ViewMode,
+};
+use crate::import;
+use crate::journal;
+use crate::save;
 use crate::utils;
+use crate::views::{view_size, StatsRow};
 use crate::CONFIGURATION;
 
-use crate::app::{App, MessageKind, StatusLine};
+use crate::app::{App, CommandHistory, MessageKind, StatusLine, UndoAction};
+
+// writes to a temporary file first, a save that is cut short
+// then never leaves a half written record behind
+fn write_habits(habits: &[&Box<dyn HabitWrapper>], file: &Path) -> Result<(), String> {
+    let j = save::to_json(habits).map_err(|e| e.to_string())?;
+    let tmp = file.with_extension("json.tmp");
+    let written = File::create(&tmp)
+        .and_then(|mut f| {
+            f.write_all(j.as_bytes())?;
+            f.sync_all()
+        })
+        .and_then(|_| fs::rename(&tmp, file));
+    written.map_err(|e| {
+        let _ = fs::remove_file(&tmp);
+        format!("Could not save to {}: {}", file.display(), e)
+    })
+}
+
+// every entry of `h` by day
+fn entries(h: &dyn HabitWrapper) -> HashMap<NaiveDate, String> {
+    h.tracked_dates()
+        .into_iter()
+        .filter_map(|d| h.raw_entry(d).map(|v| (d, v)))
+        .collect()
+}
+
+// shown in place of any change while in read-only mode
+pub const READONLY: &str = "read-only mode";
+
+// days drawn in the trend column of the stats window
+const SPARKLINE_DAYS: i64 = 30;
+
+// source and target index along with the entries to fold over
+type MergePlan = (usize, usize, Vec<(NaiveDate, String)>);
+
+// habits saved in `file`, none if there is no such file. a file
+// that does not parse is copied aside first, so the next save
+// cannot clobber the only copy, and the message says where to
+pub(crate) fn read_habits(file: PathBuf) -> (Vec<Box<dyn HabitWrapper>>, Option<String>) {
+    let mut j = String::new();
+    match File::open(&file) {
+        Ok(mut f) => {
+            if f.read_to_string(&mut j).is_err() {
+                return (
+                    Vec::new(),
+                    Some(format!("Could not read {}", file.display())),
+                );
+            }
+        }
+        Err(_) => return (Vec::new(), None),
+    }
+    match save::from_json(&j) {
+        Ok(habits) => (habits, None),
+        Err(e) => {
+            let stamp = Local::now().format("%Y%m%d%H%M%S");
+            let backup = file.with_extension(format!("json.corrupt-{}", stamp));
+            let kept = match fs::copy(&file, &backup) {
+                Ok(_) => format!("a copy is kept at {}", backup.display()),
+                Err(_) => "no copy could be made".to_owned(),
+            };
+            let warning = format!("{} could not be loaded ({}), {}", file.display(), e, kept);
+            (Vec::new(), Some(warning))
+        }
+    }
+}
+
+const WEEKDAYS: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
 
 impl App {
     pub fn new() -> Self {
+        App::with_profile(None)
+    }
+
+    pub fn with_profile(profile: Option<String>) -> Self {
         let (tx, rx) = channel();
-        let mut watcher = watcher(tx, Duration::from_secs(1)).unwrap();
-        watcher.watch(utils::auto_habit_file(), RecursiveMode::Recursive);
+        let mut watcher = watcher(tx, std::time::Duration::from_secs(1)).unwrap();
+        watcher.watch(
+            utils::auto_habit_file(profile.as_deref()),
+            RecursiveMode::Recursive,
+        );
         return App {
             habits: vec![],
             focus: 0,
+            selected: HashSet::new(),
+            jump: None,
+            readonly: false,
+            load_error: None,
+            show_archived: false,
+            filter: None,
             _file_watcher: watcher,
             file_event_recv: rx,
             view_month_offset: 0,
+            cursor: utils::today(),
             message: "Type :add <habit-name> <goal> to get started, Ctrl-L to dismiss".into(),
+            undo_stack: Default::default(),
+            history: Default::default(),
+            profile,
+            dirty: false,
+            last_save: Instant::now(),
         };
     }
 
-    pub fn add_habit(&mut self, h: Box<dyn HabitWrapper>) {
+    pub fn add_habit(&mut self, mut h: Box<dyn HabitWrapper>) {
+        if h.tracked_dates().is_empty() {
+            h.set_created(utils::today());
+        }
         self.habits.push(h);
     }
 
+    // journal every day of habit `idx` whose entry is no longer
+    // the one in `before`, for changes that touch many days at
+    // once
+    fn record_since(&self, idx: usize, before: HashMap<NaiveDate, String>) {
+        let h = &self.habits[idx];
+        let mut dates = h.tracked_dates();
+        dates.extend(before.keys());
+        dates.sort();
+        dates.dedup();
+        for d in dates {
+            let after = h.raw_entry(d);
+            if after.as_ref() != before.get(&d) {
+                journal::record(self.profile.as_deref(), &h.name(), d, after.as_deref());
+            }
+        }
+    }
+
+    // read the csv at `path` into habit `idx`
+    pub fn import_csv(&mut self, idx: usize, path: &str) -> Result<String, String> {
+        let data =
+            fs::read_to_string(path).map_err(|e| format!("Unable to read {}: {}", path, e))?;
+        let before = entries(self.habits[idx].as_ref());
+        let n = export::import_csv(self.habits[idx].as_mut(), &data).map_err(|e| e.to_string())?;
+        self.record_since(idx, before);
+        Ok(format!(
+            "Imported {} entries into `{}`",
+            n,
+            self.habits[idx].name()
+        ))
+    }
+
+    // add `h` unless its name is taken, the change is written
+    // out immediately
+    pub fn new_habit(&mut self, h: Box<dyn HabitWrapper>) -> Result<String, String> {
+        let name = h.name();
+        if self.habits.iter().any(|x| x.name() == name) {
+            return Err(format!("Habit `{}` already exists", name));
+        }
+        self.add_habit(h);
+        self.save_state()?;
+        Ok(format!("Created `{}`", name))
+    }
+
+    // rebuilds habit `idx` as a count towards `goal`, or as a
+    // yes/no habit with none, keeping its name and settings. a
+    // done day becomes 1 and a count becomes done once it reached
+    // its goal
+    pub fn convert(&mut self, idx: usize, goal: Option<u32>) -> Result<String, String> {
+        let old = &self.habits[idx];
+        let (name, auto) = (old.name(), old.is_auto());
+        let mut new: Box<dyn HabitWrapper> = match (old.kind(), goal) {
+            ("Bit", Some(goal)) => {
+                let mut count = Count::new(&name, goal, auto);
+                for d in old.tracked_dates() {
+                    if old.raw_entry(d).as_deref() == Some("1") {
+                        count.insert_raw(d, "1");
+                    }
+                }
+                Box::new(count)
+            }
+            ("Count", None) => {
+                let mut bit = Bit::new(&name, auto);
+                for d in old.tracked_dates() {
+                    // days as the count showed them, a habit that
+                    // is only logged is done on any day above zero
+                    let done = if old.goal() == 0 {
+                        old.amount(d) > 0
+                    } else {
+                        old.reached_goal(d)
+                    };
+                    bit.insert_raw(d, if done { "1" } else { "0" });
+                }
+                Box::new(bit)
+            }
+            (kind, _) => {
+                let to = if goal.is_some() {
+                    "count"
+                } else {
+                    "yes/no habit"
+                };
+                return Err(format!("Cannot turn a {} into a {}", kind, to));
+            }
+        };
+        *new.meta_mut() = old.meta().clone();
+        new.set_view_mode(old.view_mode());
+        let kind = new.kind();
+        let before = entries(old.as_ref());
+        self.habits[idx] = new;
+        self.record_since(idx, before);
+        Ok(format!("Converted `{}` to a {}", name, kind))
+    }
+
     pub fn delete_by_name(&mut self, name: &str) {
-        let old_len = self.habits.len();
-        self.habits.retain(|h| h.name() != name);
-        if old_len == self.habits.len() {
-            self.message
-                .set_message(format!("Could not delete habit `{}`", name))
+        match self.habit_index(name) {
+            Ok(idx) => self.remove_habit(idx),
+            Err(e) => self.report(Err(e)),
+        }
+    }
+
+    // indices past `idx` shift down by one, the selection
+    // is rebuilt to follow them
+    pub fn remove_habit(&mut self, idx: usize) {
+        self.habits.remove(idx);
+        self.selected = self
+            .selected
+            .iter()
+            .filter(|&&i| i != idx)
+            .map(|&i| if i > idx { i - 1 } else { i })
+            .collect();
+    }
+
+    // remove `name` for good, writing the change out immediately
+    pub fn delete_habit(&mut self, name: &str) {
+        self.delete_habits(&[name.to_owned()]);
+    }
+
+    pub fn delete_habits(&mut self, names: &[String]) {
+        for name in names {
+            self.delete_by_name(name);
+        }
+        self.focus = 0;
+        self.refocus();
+        self.save_or_report();
+    }
+
+    // full names and number of tracked days of the habits that
+    // `:delete query` would remove, the selection if there is
+    // no query
+    pub fn delete_prompt(&self, query: Option<&str>) -> Result<(Vec<String>, usize), String> {
+        if self.is_readonly() {
+            return Err(READONLY.into());
+        }
+        let targets = match query {
+            Some(q) => vec![self.habit_index(q)?],
+            None => self.selection()?,
+        };
+        let names = targets.iter().map(|&i| self.habits[i].name()).collect();
+        let days = targets
+            .iter()
+            .map(|&i| self.habits[i].tracked_dates().len())
+            .sum();
+        Ok((names, days))
+    }
+
+    pub fn toggle_selected(&mut self, idx: usize) {
+        if !self.selected.remove(&idx) {
+            self.selected.insert(idx);
+        }
+    }
+
+    pub fn is_selected(&self, idx: usize) -> bool {
+        self.selected.contains(&idx)
+    }
+
+    pub fn clear_selection(&mut self) {
+        self.selected.clear();
+    }
+
+    // selected habits in grid order
+    fn selection(&self) -> Result<Vec<usize>, String> {
+        let mut selected: Vec<usize> = self.selected.iter().cloned().collect();
+        selected.sort_unstable();
+        if selected.is_empty() {
+            return Err("No habit given and none selected".into());
+        }
+        Ok(selected)
+    }
+
+    // apply `f` to every selected habit and clear the
+    // selection, gives the number of habits `f` changed out
+    // of the number selected
+    fn for_selection<F>(&mut self, mut f: F) -> Result<(usize, usize), String>
+    where
+        F: FnMut(&mut dyn HabitWrapper) -> bool,
+    {
+        let selected = self.selection()?;
+        let changed = selected
+            .iter()
+            .filter(|&&i| f(self.habits[i].as_mut()))
+            .count();
+        self.clear_selection();
+        self.refocus();
+        Ok((changed, selected.len()))
+    }
+
+    // index of the habit that `query` refers to
+    fn habit_index(&self, query: &str) -> Result<usize, String> {
+        let names: Vec<String> = self.habits.iter().map(|h| h.name()).collect();
+        match resolve_habit(query, &names) {
+            Resolution::Exact(n) => Ok(names.iter().position(|x| x == n).unwrap()),
+            Resolution::Ambiguous(c) => Err(format!("`{}` could be: {}", query, c.join(", "))),
+            Resolution::NotFound => Err(format!("Could not find habit `{}`", query)),
+        }
+    }
+
+    // indices of habits that are currently drawn on the grid
+    pub fn visible(&self) -> Vec<usize> {
+        (0..self.habits.len())
+            .filter(|&i| self.show_archived || !self.habits[i].is_archived())
+            .filter(|&i| match &self.filter {
+                Some(tag) => self.habits[i].tags().contains(tag),
+                None => true,
+            })
+            .collect()
+    }
+
+    // only show habits carrying `tag`, or every habit
+    // if there is no tag
+    pub fn set_filter(&mut self, tag: Option<String>) -> Result<String, String> {
+        self.filter = tag.map(|t| t.trim().to_lowercase());
+        self.refocus();
+        match &self.filter {
+            Some(t) => Ok(format!("Showing habits tagged `{}`", t)),
+            None => Ok("Showing all habits".into()),
+        }
+    }
+
+    // move focus onto a visible habit if the focused one was hidden
+    pub fn refocus(&mut self) {
+        let visible = self.visible();
+        if !visible.contains(&self.focus) {
+            self.focus = visible.first().cloned().unwrap_or(0);
+        }
+    }
+
+    pub fn toggle_archived_view(&mut self) {
+        self.show_archived = !self.show_archived;
+        self.refocus();
+    }
+
+    pub fn set_archived_by_name(&mut self, name: &str, archived: bool) -> Result<String, String> {
+        let idx = self.habit_index(name)?;
+        let h = &mut self.habits[idx];
+        h.set_archived(archived);
+        let name = h.name();
+        self.refocus();
+        if archived {
+            Ok(format!("Archived `{}`", name))
+        } else {
+            Ok(format!("Unarchived `{}`", name))
+        }
+    }
+
+    pub fn record_change(&mut self, action: UndoAction) {
+        if action.before != action.after {
+            journal::record(
+                self.profile.as_deref(),
+                &action.habit,
+                action.date,
+                action.after.as_deref(),
+            );
+            self.undo_stack.push(action);
+            self.mark_dirty();
+        }
+    }
+
+    // set the entry of `name` on `date` to the raw `value`,
+    // clearing it if there is no value
+    fn restore_entry(&mut self, name: &str, date: NaiveDate, value: &Option<String>) -> bool {
+        match self.habits.iter_mut().find(|h| h.name() == name) {
+            Some(h) => {
+                match value {
+                    Some(v) => {
+                        h.insert_raw(date, v);
+                    }
+                    None => h.clear_entry(date),
+                };
+                journal::record(self.profile.as_deref(), name, date, value.as_deref());
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn history(&mut self) -> &mut CommandHistory {
+        &mut self.history
+    }
+
+    pub fn undo(&mut self) {
+        let result = match self.undo_stack.undo() {
+            Some(group) => self.restore_group(&group, true),
+            None => Err("Nothing to undo".into()),
+        };
+        if result.is_ok() {
+            self.mark_dirty();
+        }
+        self.report(result);
+    }
+
+    pub fn redo(&mut self) {
+        let result = match self.undo_stack.redo() {
+            Some(group) => self.restore_group(&group, false),
+            None => Err("Nothing to redo".into()),
+        };
+        if result.is_ok() {
+            self.mark_dirty();
+        }
+        self.report(result);
+    }
+
+    // puts back the values from before every action of `group`
+    // when undoing, or the ones after when redoing
+    fn restore_group(&mut self, group: &[UndoAction], undo: bool) -> Result<String, String> {
+        let ordered: Vec<&UndoAction> = if undo {
+            group.iter().rev().collect()
+        } else {
+            group.iter().collect()
+        };
+        for a in ordered {
+            let value = if undo { &a.before } else { &a.after };
+            if !self.restore_entry(&a.habit, a.date, value) {
+                return Err(format!("Could not find habit `{}`", a.habit));
+            }
+        }
+        let verb = if undo { "Undid" } else { "Redid" };
+        match group {
+            [a] => Ok(format!("{} change to `{}`", verb, a.habit)),
+            _ => Ok(format!("{} changes to {} habits", verb, group.len())),
         }
     }
 
+    pub fn set_note(&mut self, name: &str, date: NaiveDate, text: &str) {
+        if let Some(h) = self.habits.iter_mut().find(|h| h.name() == name) {
+            h.set_note(date, text);
+            self.dirty = true;
+        }
+    }
+
+    // brings every active habit up to its goal for today, as a
+    // single change that one undo takes back
+    pub fn complete_today(&mut self) -> String {
+        let today = utils::today();
+        let mut group = vec![];
+        for h in self.habits.iter_mut() {
+            if h.is_archived() || h.is_auto() || h.reached_goal(today) {
+                continue;
+            }
+            let raw = match h.reaching_raw() {
+                Some(r) => r,
+                None => continue,
+            };
+            let before = h.raw_entry(today);
+            if h.insert_raw(today, &raw) {
+                journal::record(self.profile.as_deref(), &h.name(), today, Some(&raw));
+                group.push(UndoAction {
+                    habit: h.name(),
+                    date: today,
+                    before,
+                    after: h.raw_entry(today),
+                });
+            }
+        }
+        if group.is_empty() {
+            return "Nothing left to do today".into();
+        }
+        let message = format!(
+            "Completed {} {} for today",
+            group.len(),
+            if group.len() == 1 { "habit" } else { "habits" }
+        );
+        self.undo_stack.push_group(group);
+        self.mark_dirty();
+        message
+    }
+
+    // set the entry of `name` on `date` to the raw `value`,
+    // typed into the value prompt
+    pub fn set_entry(&mut self, name: &str, date: NaiveDate, value: &str) -> Result<(), String> {
+        let h = match self.habits.iter_mut().find(|h| h.name() == name) {
+            Some(h) => h,
+            None => return Err(format!("Could not find habit `{}`", name)),
+        };
+        let before = h.raw_entry(date);
+        if !h.insert_raw(date, value.trim()) {
+            return Err(format!("`{}` is not a valid value", value.trim()));
+        }
+        let action = UndoAction {
+            habit: h.name(),
+            date,
+            before,
+            after: h.raw_entry(date),
+        };
+        self.record_change(action);
+        Ok(())
+    }
+
+    // repeat the entry of the day before `date` on `date`, an
+    // untracked day before leaves it alone
+    pub fn copy_previous(&mut self, idx: usize, date: NaiveDate) -> Result<String, String> {
+        if self.is_readonly() {
+            return Err(READONLY.into());
+        }
+        let h = &self.habits[idx];
+        let name = h.name();
+        if h.is_frozen(date) {
+            return Err(format!(
+                "`{}` is frozen on {}",
+                name,
+                utils::display_date(date)
+            ));
+        }
+        let prev = date.pred();
+        match h.raw_entry(prev) {
+            Some(raw) => {
+                self.set_entry(&name, date, &raw)?;
+                Ok(format!(
+                    "Copied {} of `{}` from {}",
+                    raw,
+                    name,
+                    utils::display_date(prev)
+                ))
+            }
+            None => Ok(format!(
+                "Nothing tracked for `{}` on {}",
+                name,
+                utils::display_date(prev)
+            )),
+        }
+    }
+
+    pub fn rename(&mut self, old: &str, new: &str) -> Result<String, String> {
+        if self.habits.iter().any(|h| h.name() == new) {
+            return Err(format!("Habit `{}` already exists", new));
+        }
+        let idx = self.habit_index(old)?;
+        let old = self.habits[idx].name();
+        self.habits[idx].set_name(new);
+        journal::record_rename(self.profile.as_deref(), &old, new);
+        self.save_state()?;
+        Ok(format!("Renamed `{}` to `{}`", old, new))
+    }
+
+    // reorder the board by `key`, focus and selection stay on
+    // the habits they were on
+    pub fn sort(&mut self, key: SortKey) -> String {
+        let today = utils::today();
+        let focused = self.habits.get(self.focus).map(|h| h.name());
+        let selected: Vec<String> = self
+            .selection()
+            .unwrap_or_default()
+            .iter()
+            .map(|&i| self.habits[i].name())
+            .collect();
+
+        self.habits.sort_by(|a, b| {
+            let by_key = match key {
+                SortKey::Name => std::cmp::Ordering::Equal,
+                SortKey::Remaining => b.remaining(today).cmp(&a.remaining(today)),
+                SortKey::Streak => b.get_streak(today).cmp(&a.get_streak(today)),
+            };
+            by_key.then_with(|| a.name().to_lowercase().cmp(&b.name().to_lowercase()))
+        });
+
+        let position = |name: &str| self.habits.iter().position(|h| h.name() == name);
+        let focus = focused.and_then(|n| position(&n)).unwrap_or(0);
+        let selection = selected.iter().filter_map(|n| position(n)).collect();
+        self.focus = focus;
+        self.selected = selection;
+        format!("Sorted habits by {:?}", key).to_lowercase()
+    }
+
+    // swap the habit at `idx` with its visible neighbour, the
+    // focus follows the habit around
+    pub fn move_habit(&mut self, idx: usize, up: bool) -> Result<String, String> {
+        let visible = self.visible();
+        let pos = visible.iter().position(|&i| i == idx);
+        let target = match pos {
+            Some(p) if up && p > 0 => visible[p - 1],
+            Some(p) if !up && p + 1 < visible.len() => visible[p + 1],
+            _ => {
+                return Err(format!(
+                    "Cannot move `{}` any further",
+                    self.habits[idx].name()
+                ))
+            }
+        };
+        self.habits.swap(idx, target);
+        if self.is_selected(idx) != self.is_selected(target) {
+            self.toggle_selected(idx);
+            self.toggle_selected(target);
+        }
+        self.focus = target;
+        Ok(format!("Moved `{}`", self.habits[target].name()))
+    }
+
+    // habit and days that `fill` would set
+    fn fill_plan(
+        &self,
+        name: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+        value: &str,
+    ) -> Result<(usize, Vec<NaiveDate>), String> {
+        let idx = self.habit_index(name)?;
+        let h = &self.habits[idx];
+        if !h.accepts_raw(value) {
+            return Err(format!("Invalid value `{}` for `{}`", value, h.name()));
+        }
+        let days = (0..=(to - from).num_days())
+            .map(|i| from + chrono::Duration::days(i))
+            .collect();
+        Ok((idx, days))
+    }
+
+    // set every day of `from..=to` to the raw `value`
+    pub fn fill(
+        &mut self,
+        name: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+        value: &str,
+    ) -> Result<String, String> {
+        let (idx, days) = self.fill_plan(name, from, to, value)?;
+        let h = &mut self.habits[idx];
+        for &date in days.iter() {
+            h.insert_raw(date, value);
+            journal::record(self.profile.as_deref(), &h.name(), date, Some(value));
+        }
+        let message = format!("Filled {} days of `{}`", days.len(), h.name());
+        self.save_state()?;
+        Ok(message)
+    }
+
+    // highlight the days of `name` whose entry compares to
+    // `value`, any earlier search is cleared
+    pub fn find(&mut self, name: &str, op: Comparison, value: f64) -> Result<String, String> {
+        let idx = self.habit_index(name)?;
+        self.clear_find();
+        let h = &mut self.habits[idx];
+        let found = h
+            .tracked_dates()
+            .into_iter()
+            .filter(|&d| {
+                let value_on = h.raw_entry(d).and_then(|r| r.parse::<f64>().ok());
+                matches!(value_on, Some(v) if op.holds(v, value))
+            })
+            .collect::<HashSet<_>>();
+        let message = format!("Found {} days in `{}`", found.len(), h.name());
+        h.set_highlights(found);
+        Ok(message)
+    }
+
+    pub fn clear_find(&mut self) {
+        for h in self.habits.iter_mut() {
+            h.set_highlights(HashSet::new());
+        }
+    }
+
+    // add the habits of a Loop Habit Tracker export, merging
+    // entries into habits that share a name
+    pub fn import_loop(&mut self, path: &str) -> Result<String, String> {
+        let data =
+            fs::read_to_string(path).map_err(|e| format!("Unable to read {}: {}", path, e))?;
+        let imported = import::parse_loop(&data).map_err(|e| e.to_string())?;
+        let (mut created, mut merged) = (0, 0);
+        for habit in imported {
+            let idx = match self.habits.iter().position(|h| h.name() == habit.name) {
+                Some(idx) => {
+                    merged += 1;
+                    idx
+                }
+                None => {
+                    let new: Box<dyn HabitWrapper> = if habit.is_bit {
+                        Box::new(Bit::new(&habit.name, false))
+                    } else {
+                        Box::new(Count::new(&habit.name, 1, false))
+                    };
+                    self.add_habit(new);
+                    created += 1;
+                    self.habits.len() - 1
+                }
+            };
+            for (date, value) in habit.entries {
+                let h = &mut self.habits[idx];
+                h.insert_raw(date, &value.to_string());
+                journal::record(
+                    self.profile.as_deref(),
+                    &h.name(),
+                    date,
+                    h.raw_entry(date).as_deref(),
+                );
+            }
+        }
+        Ok(format!(
+            "Imported {} new and {} existing habits",
+            created, merged
+        ))
+    }
+
+    // indices of `source` and `target` along with the entries
+    // that `merge` would fold over
+    fn merge_plan(&self, source: &str, target: &str) -> Result<MergePlan, String> {
+        let (from, to) = (self.habit_index(source)?, self.habit_index(target)?);
+        let source = self.habits[from].name();
+        if from == to {
+            return Err(format!("Cannot merge `{}` into itself", source));
+        }
+        let (from_kind, to_kind) = (self.habits[from].kind(), self.habits[to].kind());
+        if from_kind != to_kind {
+            return Err(format!(
+                "Cannot merge a {} into a {}",
+                from_kind.to_lowercase(),
+                to_kind.to_lowercase()
+            ));
+        }
+        let entries = self.habits[from]
+            .tracked_dates()
+            .into_iter()
+            .filter_map(|d| self.habits[from].raw_entry(d).map(|v| (d, v)))
+            .collect();
+        Ok((from, to, entries))
+    }
+
+    // fold every entry of `source` into `target`, then
+    // delete `source`
+    pub fn merge(&mut self, source: &str, target: &str) -> Result<String, String> {
+        let (from, to, entries) = self.merge_plan(source, target)?;
+        let (source, target) = (self.habits[from].name(), self.habits[to].name());
+        for (date, value) in entries.iter() {
+            let h = &mut self.habits[to];
+            h.merge_raw(*date, value);
+            journal::record(
+                self.profile.as_deref(),
+                &target,
+                *date,
+                h.raw_entry(*date).as_deref(),
+            );
+        }
+        self.delete_habit(&source);
+        Ok(format!(
+            "Merged {} days of `{}` into `{}`",
+            entries.len(),
+            source,
+            target
+        ))
+    }
+
+    // apply the journal at `path` to the habits of the same name
+    pub fn replay(&mut self, path: &str) -> Result<String, String> {
+        let data =
+            fs::read_to_string(path).map_err(|e| format!("Unable to read {}: {}", path, e))?;
+        let (applied, skipped) = journal::replay(&mut self.habits, &data);
+        Ok(format!("Replayed {} changes, skipped {}", applied, skipped))
+    }
+
+    // drop entries of zero, for every kind of habit they
+    // look and count just like an untracked day
+    pub fn repair(&mut self) -> String {
+        let plan = self.repair_plan();
+        for &(idx, d) in plan.iter() {
+            let h = &mut self.habits[idx];
+            h.clear_entry(d);
+            journal::record(self.profile.as_deref(), &h.name(), d, None);
+        }
+        format!("Pruned {} empty entries", plan.len())
+    }
+
+    // habit and day of every entry `repair` would drop
+    fn repair_plan(&self) -> Vec<(usize, NaiveDate)> {
+        let mut plan = vec![];
+        for (idx, h) in self.habits.iter().enumerate() {
+            for d in h.tracked_dates() {
+                let value = h.raw_entry(d).and_then(|r| r.parse::<f64>().ok());
+                if value == Some(0.0) {
+                    plan.push((idx, d));
+                }
+            }
+        }
+        plan
+    }
+
+    // what `command` would do, leaving every habit as it is
+    pub fn dry_run(&self, command: &Command) -> Result<String, String> {
+        match command {
+            Command::Fill(name, from, to, value) => {
+                let (idx, days) = self.fill_plan(name, *from, *to, value)?;
+                let h = &self.habits[idx];
+                let new = days.iter().filter(|&&d| h.raw_entry(d).is_none()).count();
+                Ok(format!(
+                    "Would create {} and overwrite {} entries of `{}`",
+                    new,
+                    days.len() - new,
+                    h.name()
+                ))
+            }
+            Command::Merge(source, target) => {
+                let (from, to, entries) = self.merge_plan(source, target)?;
+                Ok(format!(
+                    "Would merge {} days of `{}` into `{}` and delete `{}`",
+                    entries.len(),
+                    self.habits[from].name(),
+                    self.habits[to].name(),
+                    self.habits[from].name()
+                ))
+            }
+            Command::Delete(name) | Command::ForceDelete(name) => {
+                let (names, days) = self.delete_prompt(name.as_deref())?;
+                Ok(format!(
+                    "Would delete {} habit(s) ({}) and {} entries",
+                    names.len(),
+                    names.join(", "),
+                    days
+                ))
+            }
+            Command::Repair => Ok(format!(
+                "Would prune {} empty entries",
+                self.repair_plan().len()
+            )),
+            _ => Err("Nothing to preview".into()),
+        }
+    }
+
+    // best and worst weekday of a habit, weekdays it has never
+    // counted on are listed as n/a
+    pub fn insights(&self, idx: usize) -> Result<String, String> {
+        let h = &self.habits[idx];
+        let rates = h.weekday_rates();
+        let sampled = (0..7).filter_map(|w| rates[w].map(|r| (w, r)));
+        let best = sampled
+            .clone()
+            .fold(None, |acc: Option<(usize, f64)>, (w, r)| match acc {
+                Some((_, b)) if b >= r => acc,
+                _ => Some((w, r)),
+            });
+        let worst = sampled.fold(None, |acc: Option<(usize, f64)>, (w, r)| match acc {
+            Some((_, b)) if b <= r => acc,
+            _ => Some((w, r)),
+        });
+        let (best, worst) = match (best, worst) {
+            (Some(b), Some(w)) => (b, w),
+            _ => return Err(format!("No history for `{}` yet", h.name())),
+        };
+        let mut message = format!(
+            "{}: Best: {} {:.0}%, Worst: {} {:.0}%",
+            h.name(),
+            WEEKDAYS[best.0],
+            best.1 * 100.,
+            WEEKDAYS[worst.0],
+            worst.1 * 100.
+        );
+        let missing = (0..7)
+            .filter(|&w| rates[w].is_none())
+            .map(|w| WEEKDAYS[w])
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            message.push_str(&format!(", n/a: {}", missing.join(" ")));
+        }
+        Ok(message)
+    }
+
+    // what is stored for a habit, to check it against memory
+    pub fn info(&self, idx: usize) -> Result<String, String> {
+        let h = &self.habits[idx];
+        let dates = h.tracked_dates();
+        let (first, last) = match (dates.iter().min(), dates.iter().max()) {
+            (Some(&f), Some(&l)) => (f, l),
+            _ => return Ok(format!("{}: no data", h.name())),
+        };
+        Ok(format!(
+            "{}: {} to {}, {} days tracked, streak {} (longest {}), total {}",
+            h.name(),
+            utils::display_date(first),
+            utils::display_date(last),
+            dates.len(),
+            h.get_streak(utils::today()),
+            h.longest_streak(),
+            h.total()
+        ))
+    }
+
+    pub fn streak_report(&self) -> String {
+        let today = utils::today();
+        let habits = self
+            .habits
+            .iter()
+            .filter(|h| !h.is_archived())
+            .map(|h| h.as_ref())
+            .collect::<Vec<_>>();
+        export::streak_table(&habits, today)
+    }
+
+    // every habit that is not archived, by name or as json
+    pub fn list(&self, json: bool) -> Result<String, String> {
+        let habits = self
+            .habits
+            .iter()
+            .filter(|h| !h.is_archived())
+            .map(|h| h.as_ref())
+            .collect::<Vec<_>>();
+        if json {
+            export::to_list_json(&habits, utils::today()).map_err(|e| e.to_string())
+        } else {
+            Ok(export::to_list(&habits))
+        }
+    }
+
+    pub fn month_stats(&self, offset: u32) -> Vec<StatsRow> {
+        let first = utils::view_month(offset);
+        let today = utils::today();
+        let last = utils::last_day_of_month(first).min(today);
+        self.habits
+            .iter()
+            .filter(|h| !h.is_archived())
+            .map(|h| StatsRow {
+                name: h.name(),
+                total: h.sum_in_month(first),
+                completed: (0..=(last - first).num_days())
+                    .map(|i| first + chrono::Duration::days(i))
+                    .filter(|&d| h.reached_goal(d))
+                    .count() as u32,
+                rate: h.completion_rate(first, last),
+                streak: h.get_streak(last),
+                trend: utils::sparkline(
+                    &(0..SPARKLINE_DAYS)
+                        .rev()
+                        .map(|i| h.amount(last - chrono::Duration::days(i)))
+                        .collect::<Vec<_>>(),
+                ),
+            })
+            .collect()
+    }
+
     pub fn get_mode(&self) -> ViewMode {
         if self.habits.is_empty() {
             return ViewMode::Day;
@@ -66,6 +985,35 @@ impl App {
         }
     }
 
+    // move the cursor by `days`, never past today, the month
+    // on display follows it around. weekends are stepped over
+    // when the focused habit leaves them out
+    pub fn move_cursor(&mut self, days: i64) {
+        let today = utils::today();
+        let mut cursor = self.cursor + chrono::Duration::days(days);
+        let skip = self
+            .habits
+            .get(self.focus)
+            .is_some_and(|h| h.skips_weekends());
+        while skip && days != 0 && utils::is_weekend(cursor) {
+            cursor += chrono::Duration::days(days.signum());
+        }
+        let cursor = cursor.min(today);
+        self.set_cursor(cursor);
+        self.set_view_month_offset(utils::month_offset(cursor));
+    }
+
+    pub fn set_cursor(&mut self, date: NaiveDate) {
+        self.cursor = date;
+        for v in self.habits.iter_mut() {
+            v.set_cursor(date);
+        }
+    }
+
+    pub fn cursor(&self) -> NaiveDate {
+        self.cursor
+    }
+
     pub fn sift_backward(&mut self) {
         self.view_month_offset += 1;
         for v in self.habits.iter_mut() {
@@ -82,132 +1030,367 @@ impl App {
         }
     }
 
+    pub fn set_readonly(&mut self, on: bool) {
+        self.readonly = on;
+    }
+
+    // read-only by choice, or because a save file that did not
+    // load must not be written over
+    pub fn is_readonly(&self) -> bool {
+        self.readonly || self.load_error.is_some()
+    }
+
+    pub fn load_error(&self) -> Option<&str> {
+        self.load_error.as_deref()
+    }
+
+    pub fn start_jump(&mut self) {
+        self.jump = Some((String::new(), self.focus));
+        self.report(Ok("/".to_owned()));
+    }
+
+    // feeds a key to an ongoing `/` jump, returns false when
+    // there is none
+    pub fn jump_event(&mut self, e: &Event) -> bool {
+        let (mut prefix, origin) = match self.jump.take() {
+            Some(j) => j,
+            None => return false,
+        };
+        match e {
+            Event::Char(c) => prefix.push(*c),
+            Event::Key(Key::Backspace) => {
+                prefix.pop();
+            }
+            Event::Key(Key::Enter) => {
+                self.clear_message();
+                return true;
+            }
+            Event::Key(Key::Esc) => {
+                self.focus = origin;
+                self.clear_message();
+                return true;
+            }
+            _ => {
+                self.jump = Some((prefix, origin));
+                return true;
+            }
+        }
+        let query = prefix.to_lowercase();
+        let found = self
+            .visible()
+            .into_iter()
+            .find(|&i| self.habits[i].name().to_lowercase().starts_with(&query));
+        match found {
+            Some(i) => {
+                self.focus = i;
+                self.report(Ok(format!("/{}", prefix)));
+            }
+            None => self.report(Err(format!("/{} (no match)", prefix))),
+        }
+        self.jump = Some((prefix, origin));
+        true
+    }
+
     pub fn set_focus(&mut self, d: Absolute) {
         let grid_width = CONFIGURATION.grid_width;
+        let visible = self.visible();
+        if visible.is_empty() {
+            return;
+        }
+        let mut pos = visible.iter().position(|&i| i == self.focus).unwrap_or(0);
         match d {
             Absolute::Right => {
-                if self.focus != self.habits.len() - 1 {
-                    self.focus += 1;
-                }
+                pos = (pos + 1).min(visible.len() - 1);
             }
             Absolute::Left => {
-                if self.focus != 0 {
-                    self.focus -= 1;
-                }
+                pos = pos.saturating_sub(1);
             }
             Absolute::Down => {
-                if self.focus + grid_width < self.habits.len() - 1 {
-                    self.focus += grid_width;
-                } else {
-                    self.focus = self.habits.len() - 1;
-                }
+                pos = (pos + grid_width).min(visible.len() - 1);
             }
             Absolute::Up => {
-                if self.focus as isize - grid_width as isize >= 0 {
-                    self.focus -= grid_width;
-                } else {
-                    self.focus = 0;
-                }
+                pos = pos.saturating_sub(grid_width);
             }
             Absolute::None => {}
         }
+        self.focus = visible[pos];
+    }
+
+    // show the outcome of a command in the message line
+    pub(crate) fn report(&mut self, result: Result<String, String>) {
+        match result {
+            Ok(m) => {
+                self.message.set_message(m);
+                self.message.set_kind(MessageKind::Info);
+            }
+            Err(m) => {
+                self.message.set_message(m);
+                self.message.set_kind(MessageKind::Error);
+            }
+        }
     }
 
     pub fn clear_message(&mut self) {
         self.message.clear();
     }
 
+    // habits that are yet to reach today's goal, archived
+    // habits are left out
+    fn pending_today(&self) -> Vec<&dyn HabitWrapper> {
+        let today = utils::today();
+        self.habits
+            .iter()
+            .filter(|h| !h.is_archived() && !h.is_paused(today) && !h.reached_goal(today))
+            .map(|h| h.as_ref())
+            .collect()
+    }
+
+    // one line per habit still short of today's goal, none once
+    // everything is done
+    pub fn reminders(&self) -> Option<String> {
+        let today = utils::today();
+        let lines = self
+            .pending_today()
+            .iter()
+            .filter(|h| h.remaining(today) > 0)
+            .map(|h| {
+                let mut line = format!("{} ({} left", h.name(), h.remaining(today));
+                if h.streak_at_risk(today) {
+                    let streak = h.get_streak(today.pred());
+                    line.push_str(&format!(", {} day streak at risk", streak));
+                }
+                line + ")"
+            })
+            .collect::<Vec<_>>();
+        if lines.is_empty() {
+            return None;
+        }
+        Some(lines.join("\n"))
+    }
+
+    pub fn all_done_today(&self) -> bool {
+        !self.habits.is_empty() && self.pending_today().is_empty()
+    }
+
+    // the streak of habit `idx` when doing today just brought
+    // it onto a milestone, later days of the same streak do not
+    // count as reaching it again
+    pub fn new_milestone(&self, idx: usize, was_reached: bool) -> Option<u32> {
+        let today = utils::today();
+        let h = &self.habits[idx];
+        if was_reached || !h.reached_goal(today) {
+            return None;
+        }
+        let streak = h.get_streak(today);
+        Some(streak).filter(|s| CONFIGURATION.milestones.contains(s))
+    }
+
+    // called when the last habit of the day was just done
+    pub fn celebrate(&mut self) {
+        if CONFIGURATION.bell {
+            let mut out = std::io::stdout();
+            out.write_all(b"\x07");
+            out.flush();
+        }
+        self.report(Ok("All habits done for today!".into()));
+    }
+
+    // amount left to do today, summed across habits
+    pub fn remaining_total(&self) -> u32 {
+        let today = utils::today();
+        self.pending_today()
+            .iter()
+            .map(|h| h.remaining(today))
+            .fold(0, u32::saturating_add)
+    }
+
     pub fn status(&self) -> StatusLine {
-        let today = chrono::Local::now().naive_local().date();
-        let remaining = self.habits.iter().map(|h| h.remaining(today)).sum::<u32>();
-        let total = self.habits.iter().map(|h| h.goal()).sum::<u32>();
-        let completed = total - remaining;
+        let today = utils::today();
+        let active = self
+            .habits
+            .iter()
+            .filter(|h| !h.is_archived() && !h.is_paused(today));
+        let remaining = self.remaining_total();
+        let total = active.map(|h| h.goal()).fold(0, u32::saturating_add);
+        let completed = total.saturating_sub(remaining);
+        let pending = self.pending_today().len();
 
-        let timestamp = if self.view_month_offset == 0 {
-            format!("{}", Local::now().date().format("%d/%b/%y"),)
+        let mut timestamp = if self.view_month_offset == 0 {
+            utils::display_date(utils::today())
         } else {
-            let months = self.view_month_offset;
-            format!("{}", format!("{} months ago", months),)
+            let month = utils::view_month(self.view_month_offset);
+            let period = match self.get_mode() {
+                ViewMode::Year => month.format(&CONFIGURATION.year_format),
+                _ => month.format(&CONFIGURATION.month_format),
+            };
+            format!("{} ({} months ago)", period, self.view_month_offset)
         };
 
+        if let Some(p) = &self.profile {
+            timestamp = format!("[{}] {}", p, timestamp);
+        }
+        if self.is_readonly() {
+            timestamp = format!("[read-only] {}", timestamp);
+        }
+
         StatusLine {
             0: format!(
-                "Today: {} completed, {} remaining --{}--",
+                "Today: {} completed, {} remaining, {} {} left --{}--",
                 completed,
                 remaining,
+                pending,
+                if pending == 1 { "habit" } else { "habits" },
                 self.get_mode()
             ),
             1: timestamp,
         }
     }
 
-    pub fn max_size(&self) -> Vec2 {
-        let grid_width = CONFIGURATION.grid_width;
-        let width = grid_width * CONFIGURATION.view_width;
-        let height = {
-            if !self.habits.is_empty() {
-                (CONFIGURATION.view_height as f64
-                    * (self.habits.len() as f64 / grid_width as f64).ceil())
-                    as usize
-            } else {
-                0
+    // offset of every visible habit on the grid along with the
+    // size of the whole grid, rows hold `grid_width` habits and
+    // are as tall as their tallest habit
+    pub fn grid_layout(&self) -> (Vec<(usize, Vec2)>, Vec2) {
+        let mut offsets = vec![];
+        let mut size = Vec2::zero();
+        for row in self.visible().chunks(CONFIGURATION.grid_width) {
+            let mut x = 0;
+            let mut height = 0;
+            for &idx in row {
+                let view = view_size(self.habits[idx].view_mode());
+                offsets.push((idx, Vec2::new(x, size.y)));
+                x += view.x + 2;
+                height = height.max(view.y);
             }
-        };
-        Vec2::new(width, height + 2)
+            size = Vec2::new(size.x.max(x), size.y + height);
+        }
+        (offsets, size)
     }
 
-    pub fn load_state() -> Self {
-        let (regular_f, auto_f) = (utils::habit_file(), utils::auto_habit_file());
-        let read_from_file = |file: PathBuf| -> Vec<Box<dyn HabitWrapper>> {
-            if let Ok(ref mut f) = File::open(file) {
-                let mut j = String::new();
-                f.read_to_string(&mut j);
-                return serde_json::from_str(&j).unwrap();
-            } else {
-                return Vec::new();
-            }
-        };
+    pub fn max_size(&self) -> Vec2 {
+        let grid = self.grid_layout().1;
+        let width =
+            (CONFIGURATION.grid_width * CONFIGURATION.view_width).max(grid.x.saturating_sub(2));
+        Vec2::new(width, grid.y + 2)
+    }
 
-        let mut regular = read_from_file(regular_f);
-        let auto = read_from_file(auto_f);
+    pub fn load_state(profile: Option<String>) -> Self {
+        let (regular_f, auto_f) = (
+            utils::habit_file(profile.as_deref()),
+            utils::auto_habit_file(profile.as_deref()),
+        );
+        let mut warnings = CONFIGURATION.warnings.clone();
+        let (mut regular, bad_regular) = read_habits(regular_f);
+        let (auto, bad_auto) = read_habits(auto_f);
+        let bad = bad_regular.into_iter().chain(bad_auto).collect::<Vec<_>>();
+        let load_error = Some(bad.join(", ")).filter(|_| !bad.is_empty());
+        if let Some(e) = &load_error {
+            warnings.push(format!("{}, changes are off until it is fixed", e));
+        }
         regular.extend(auto);
-        return App {
+        let offset = regular.first().map_or(0, |h| h.view_month_offset());
+        let mut app = App {
             habits: regular,
-            ..Default::default()
+            load_error,
+            ..App::with_profile(profile)
         };
+        app.set_view_month_offset(offset);
+        if let Some(key) = CONFIGURATION.sort_order {
+            app.sort(key);
+        }
+        if !warnings.is_empty() {
+            app.report(Err(warnings.join(", ")));
+        }
+        app
+    }
+
+    // save the current profile and carry on with `profile`
+    pub fn switch_profile(&mut self, profile: Option<String>) -> Result<String, String> {
+        if let Some(p) = &profile {
+            if !utils::valid_profile(p) {
+                return Err(format!("Invalid profile name `{}`", p));
+            }
+        }
+        if profile == self.profile {
+            return Err("Already on that profile".into());
+        }
+        if self.load_error.is_none() {
+            self.save_state()?;
+        }
+        let history = std::mem::take(&mut self.history);
+        let readonly = self.readonly;
+        *self = App::load_state(profile);
+        self.history = history;
+        self.readonly = readonly;
+        Ok(format!(
+            "Switched to profile `{}`",
+            self.profile.as_deref().unwrap_or("default")
+        ))
+    }
+
+    pub fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    pub fn has_unsaved_work(&self) -> bool {
+        self.dirty
+    }
+
+    // write out unsaved changes once `autosave_interval` seconds
+    // have passed since the last save
+    pub fn autosave(&mut self) {
+        let interval = CONFIGURATION.autosave_interval;
+        if self.dirty && interval > 0 && self.last_save.elapsed().as_secs() >= interval {
+            self.save_or_report();
+        }
     }
 
     // this function does IO
     // TODO: convert this into non-blocking async function
-    pub fn save_state(&self) {
+    pub fn save_state(&mut self) -> Result<(), String> {
+        if let Some(e) = &self.load_error {
+            return Err(format!("Not saving, {}", e));
+        }
         let (regular, auto): (Vec<_>, Vec<_>) = self.habits.iter().partition(|&x| !x.is_auto());
-        let (regular_f, auto_f) = (utils::habit_file(), utils::auto_habit_file());
-
-        let write_to_file = |data: Vec<&Box<dyn HabitWrapper>>, file: PathBuf| {
-            let j = serde_json::to_string_pretty(&data).unwrap();
-            match OpenOptions::new()
-                .write(true)
-                .create(true)
-                .truncate(true)
-                .open(file)
-            {
-                Ok(ref mut f) => f.write_all(j.as_bytes()).unwrap(),
-                Err(_) => panic!("Unable to write!"),
-            };
-        };
+        let profile = self.profile.as_deref();
+        let (regular_f, auto_f) = (utils::habit_file(profile), utils::auto_habit_file(profile));
+        let written = write_habits(&regular, &regular_f).and_then(|_| write_habits(&auto, &auto_f));
+        // a failed save is tried again after the next interval
+        // rather than every second
+        self.last_save = Instant::now();
+        written?;
+        self.dirty = false;
+        Ok(())
+    }
 
-        write_to_file(regular, regular_f);
-        write_to_file(auto, auto_f);
+    // the outcome of a save, shown only when it failed
+    pub fn save_or_report(&mut self) {
+        if let Err(e) = self.save_state() {
+            self.report(Err(e));
+        }
     }
 
     pub fn parse_command(&mut self, result: Result<Command, CommandLineError>) {
+        if self.is_readonly() && matches!(result, Ok(ref c) if c.is_edit()) {
+            self.report(Err(READONLY.into()));
+            return;
+        }
+        if matches!(result, Ok(ref c) if c.is_edit()) {
+            self.mark_dirty();
+        }
         let mut _track = |name: &str, event: TrackEvent| {
             let target_habit = self
                 .habits
                 .iter_mut()
                 .find(|x| x.name() == name && x.is_auto());
             if let Some(h) = target_habit {
-                h.modify(Local::now().naive_local().date(), event);
+                let today = utils::today();
+                h.modify(today, event);
+                journal::record(
+                    self.profile.as_deref(),
+                    &h.name(),
+                    today,
+                    h.raw_entry(today).as_deref(),
+                );
             }
         };
         match result {
@@ -220,17 +1403,378 @@ impl App {
                         self.add_habit(Box::new(Bit::new(name, auto)));
                     }
                 }
+                Command::AddFloat(name, goal, auto) => {
+                    self.add_habit(Box::new(FloatCount::new(name, goal, auto)));
+                }
+                Command::AddDuration(name, goal, auto) => {
+                    self.add_habit(Box::new(Duration::new(name, goal, auto)));
+                }
+                Command::AddPeriodic(name, goal, period, auto) => {
+                    let mut habit = Count::new(name, goal, auto);
+                    habit.set_goal_period(period);
+                    self.add_habit(Box::new(habit));
+                }
+                Command::AddCapped(name, goal, auto) => {
+                    self.add_habit(Box::new(Capped::new(name, goal, auto)));
+                }
+                Command::AddChecklist(name, tasks, auto) => {
+                    self.add_habit(Box::new(Checklist::new(name, tasks, auto)));
+                }
+                Command::NewCount(name, goal) => {
+                    let result = self.new_habit(Box::new(Count::new(name, goal, false)));
+                    self.report(result);
+                }
+                Command::Clone(source, name) => {
+                    let result = self
+                        .habit_index(&source)
+                        .and_then(|idx| self.new_habit(self.habits[idx].clone_config(&name)));
+                    self.report(result);
+                }
+                Command::Convert(name, goal) => {
+                    let result = self
+                        .habit_index(&name)
+                        .and_then(|idx| self.convert(idx, goal));
+                    self.report(result);
+                }
+                Command::NewBit(name) => {
+                    let result = self.new_habit(Box::new(Bit::new(name, false)));
+                    self.report(result);
+                }
                 Command::Delete(name) => {
-                    self.delete_by_name(&name);
-                    self.focus = 0;
+                    // the prompt itself is opened in `call_on_app`
+                    if let Err(e) = self.delete_prompt(name.as_deref()) {
+                        self.report(Err(e));
+                    }
                 }
+                Command::ForceDelete(name) => match self.delete_prompt(name.as_deref()) {
+                    Ok((names, _)) => self.delete_habits(&names),
+                    Err(e) => self.report(Err(e)),
+                },
                 Command::TrackUp(name) => {
                     _track(&name, TrackEvent::Increment);
                 }
                 Command::TrackDown(name) => {
                     _track(&name, TrackEvent::Decrement);
                 }
-                Command::Quit => self.save_state(),
+                Command::ExportCsv(name, path) => {
+                    let result = self.habit_index(&name).and_then(|idx| {
+                        let h = &self.habits[idx];
+                        export::write_to(&path, &export::to_csv(h.as_ref()))
+                            .map(|_| format!("Exported `{}` to {}", h.name(), path))
+                            .map_err(|e| format!("Unable to write {}: {}", path, e))
+                    });
+                    self.report(result);
+                }
+                Command::ExportIcs(name, path) => {
+                    let result = self.habit_index(&name).and_then(|idx| {
+                        let h = &self.habits[idx];
+                        export::write_to(&path, &export::to_ics(h.as_ref()))
+                            .map(|_| format!("Exported `{}` to {}", h.name(), path))
+                            .map_err(|e| format!("Unable to write {}: {}", path, e))
+                    });
+                    self.report(result);
+                }
+                Command::ExportMatrix(path) => {
+                    let result = export::write_to(&path, &export::to_matrix(&self.habits))
+                        .map(|_| format!("Exported {} habits to {}", self.habits.len(), path))
+                        .map_err(|e| format!("Unable to write {}: {}", path, e));
+                    self.report(result);
+                }
+                Command::ExportJson(path) => {
+                    let result = export::to_json(&self.habits)
+                        .map_err(|e| e.to_string())
+                        .and_then(|j| {
+                            export::write_to(&path, &j)
+                                .map_err(|e| format!("Unable to write {}: {}", path, e))
+                        })
+                        .map(|_| format!("Exported {} habits to {}", self.habits.len(), path));
+                    self.report(result);
+                }
+                Command::ImportLoop(path) => {
+                    let result = self.import_loop(&path);
+                    self.report(result);
+                }
+                Command::ImportCsv(name, path) => {
+                    let result = self
+                        .habit_index(&name)
+                        .and_then(|idx| self.import_csv(idx, &path));
+                    self.report(result);
+                }
+                Command::Color(name, color) => {
+                    let unknown = color.as_ref().filter(|c| utils::parse_color(c).is_none());
+                    let result = match unknown {
+                        Some(c) => Err(format!("Unknown color `{}`", c)),
+                        None => self.habit_index(&name).map(|idx| {
+                            let h = &mut self.habits[idx];
+                            h.set_color(color);
+                            format!("Updated color of `{}`", h.name())
+                        }),
+                    };
+                    self.report(result);
+                }
+                Command::Archive(Some(name)) => {
+                    let result = self.set_archived_by_name(&name, true);
+                    self.report(result);
+                }
+                Command::Unarchive(Some(name)) => {
+                    let result = self.set_archived_by_name(&name, false);
+                    self.report(result);
+                }
+                Command::Archive(None) | Command::Unarchive(None) => {
+                    let archive = matches!(c, Command::Archive(_));
+                    let result = self.for_selection(|h| {
+                        let changed = h.is_archived() != archive;
+                        h.set_archived(archive);
+                        changed
+                    });
+                    let verb = if archive { "Archived" } else { "Unarchived" };
+                    self.report(result.map(|(n, _)| format!("{} {} habit(s)", verb, n)));
+                }
+                Command::Rename(old, new) => {
+                    let result = self.rename(&old, &new);
+                    self.report(result);
+                }
+                Command::Goal(name, goal) => {
+                    let result = self.habit_index(&name).and_then(|idx| {
+                        let h = &mut self.habits[idx];
+                        if h.set_goal_raw(&goal.to_string()) {
+                            Ok(format!("Set goal of `{}` to {}", h.name(), goal))
+                        } else {
+                            Err(format!("Cannot change the goal of `{}`", h.name()))
+                        }
+                    });
+                    self.report(result);
+                }
+                Command::Tag(None, tag) => {
+                    let result = self
+                        .for_selection(|h| h.add_tag(&tag))
+                        .map(|(n, _)| format!("Tagged {} habit(s) with `{}`", n, tag));
+                    self.report(result);
+                }
+                Command::Untag(None, tag) => {
+                    let result = self
+                        .for_selection(|h| h.remove_tag(&tag))
+                        .map(|(n, _)| format!("Removed tag `{}` from {} habit(s)", tag, n));
+                    self.report(result);
+                }
+                Command::Tag(Some(name), tag) => {
+                    let result = self.habit_index(&name).and_then(|idx| {
+                        let h = &mut self.habits[idx];
+                        if h.add_tag(&tag) {
+                            Ok(format!("Tagged `{}` with `{}`", h.name(), tag))
+                        } else {
+                            Err(format!("`{}` is already tagged `{}`", h.name(), tag))
+                        }
+                    });
+                    self.report(result);
+                }
+                Command::Untag(Some(name), tag) => {
+                    let result = self.habit_index(&name).and_then(|idx| {
+                        let h = &mut self.habits[idx];
+                        if h.remove_tag(&tag) {
+                            Ok(format!("Removed tag `{}` from `{}`", tag, h.name()))
+                        } else {
+                            Err(format!("`{}` is not tagged `{}`", h.name(), tag))
+                        }
+                    });
+                    self.report(result);
+                }
+                Command::Find(name, op, value) => {
+                    let result = self.find(&name, op, value);
+                    self.report(result);
+                }
+                Command::FindClear => self.clear_find(),
+                Command::Pause(name, until) => {
+                    let today = utils::today();
+                    let result = self.habit_index(&name).and_then(|idx| {
+                        if until <= today {
+                            return Err("A pause has to end after today".to_owned());
+                        }
+                        let h = &mut self.habits[idx];
+                        h.set_pause(today, Some(until));
+                        Ok(format!(
+                            "Paused `{}` until {}",
+                            h.name(),
+                            utils::display_date(until)
+                        ))
+                    });
+                    self.report(result);
+                }
+                Command::Resume(name) => {
+                    let result = self.habit_index(&name).and_then(|idx| {
+                        let h = &mut self.habits[idx];
+                        if h.paused_until().is_none() {
+                            return Err(format!("`{}` is not paused", h.name()));
+                        }
+                        h.set_pause(utils::today(), None);
+                        Ok(format!("Resumed `{}`", h.name()))
+                    });
+                    self.report(result);
+                }
+                Command::Weekends(name, show) => {
+                    let result = self.habit_index(&name).map(|idx| {
+                        let h = &mut self.habits[idx];
+                        h.set_skip_weekends(!show);
+                        format!("Updated `{}`", h.name())
+                    });
+                    self.report(result);
+                }
+                Command::Glyphs(name, true_chr, false_chr) => {
+                    let result = self.habit_index(&name).map(|idx| {
+                        let h = &mut self.habits[idx];
+                        h.set_glyphs(true_chr, false_chr);
+                        format!("Updated characters of `{}`", h.name())
+                    });
+                    self.report(result);
+                }
+                Command::Set(name, date, value) => {
+                    let result = self.habit_index(&name).and_then(|idx| {
+                        let name = self.habits[idx].name();
+                        self.set_entry(&name, date, &value)?;
+                        self.save_state()?;
+                        Ok(format!(
+                            "Set `{}` on {} to {}",
+                            name,
+                            utils::display_date(date),
+                            value
+                        ))
+                    });
+                    self.report(result);
+                }
+                Command::Fill(name, from, to, value) => {
+                    let result = self.fill(&name, from, to, &value);
+                    self.report(result);
+                }
+                Command::Schedule(name, schedule) => {
+                    let result = self.habit_index(&name).map(|idx| {
+                        let h = &mut self.habits[idx];
+                        h.set_schedule(schedule);
+                        format!("Updated schedule of `{}`", h.name())
+                    });
+                    self.report(result);
+                }
+                Command::Filter(tag) => {
+                    let result = self.set_filter(tag);
+                    self.report(result);
+                }
+                Command::View(mode) => {
+                    for habit in self.habits.iter_mut() {
+                        habit.set_view_mode(mode);
+                    }
+                }
+                Command::MoveUp(name) => {
+                    let result = self
+                        .habit_index(&name)
+                        .and_then(|idx| self.move_habit(idx, true));
+                    self.report(result);
+                }
+                Command::MoveDown(name) => {
+                    let result = self
+                        .habit_index(&name)
+                        .and_then(|idx| self.move_habit(idx, false));
+                    self.report(result);
+                }
+                Command::ReportStreaks(Some(path)) => {
+                    let result = export::write_to(&path, &self.streak_report())
+                        .map(|_| format!("Wrote streak report to {}", path))
+                        .map_err(|e| format!("Unable to write {}: {}", path, e));
+                    self.report(result);
+                }
+                // the report window is opened in `call_on_app`
+                Command::ReportStreaks(None) => {}
+                // as is the list
+                Command::List(_) => {}
+                Command::Insights(name) => {
+                    let result = self.habit_index(&name).and_then(|idx| self.insights(idx));
+                    self.report(result);
+                }
+                Command::Info(name) => {
+                    let result = self.habit_index(&name).and_then(|idx| self.info(idx));
+                    self.report(result);
+                }
+                Command::Replay(path) => {
+                    let result = self.replay(&path);
+                    self.report(result);
+                }
+                Command::Sort(key) => {
+                    let message = self.sort(key);
+                    self.report(Ok(message));
+                }
+                Command::DryRun(command) => {
+                    let result = self.dry_run(&command);
+                    self.report(result);
+                }
+                Command::Profile(profile) => {
+                    let result = self.switch_profile(profile);
+                    self.report(result);
+                }
+                Command::Repair => {
+                    let message = self.repair();
+                    self.report(Ok(message));
+                }
+                Command::Merge(source, target) => {
+                    let result = self.merge(&source, &target);
+                    self.report(result);
+                }
+                Command::Negative(name, allow) => {
+                    let result = self.habit_index(&name).and_then(|idx| {
+                        let h = &mut self.habits[idx];
+                        if h.set_allow_negative(allow) {
+                            Ok(format!("Updated `{}`", h.name()))
+                        } else {
+                            Err(format!("`{}` cannot go below zero", h.name()))
+                        }
+                    });
+                    self.report(result);
+                }
+                Command::Step(name, step) => {
+                    let result = self.habit_index(&name).and_then(|idx| {
+                        let h = &mut self.habits[idx];
+                        if h.set_step(step) {
+                            Ok(format!("`{}` now changes by {}", h.name(), step))
+                        } else {
+                            Err(format!("`{}` always changes by one", h.name()))
+                        }
+                    });
+                    self.report(result);
+                }
+                Command::Target(name, target) => {
+                    let result = self.habit_index(&name).and_then(|idx| {
+                        let h = &mut self.habits[idx];
+                        if h.set_target(target) {
+                            Ok(format!("Updated `{}`", h.name()))
+                        } else {
+                            Err(format!("`{}` cannot have a target total", h.name()))
+                        }
+                    });
+                    self.report(result);
+                }
+                Command::Rolling(name, rolling) => {
+                    let result = self.habit_index(&name).and_then(|idx| {
+                        let h = &mut self.habits[idx];
+                        if h.set_rolling(rolling) {
+                            Ok(format!("Updated `{}`", h.name()))
+                        } else {
+                            Err("Only yes/no habits can have a rolling goal".into())
+                        }
+                    });
+                    self.report(result);
+                }
+                Command::Undo => self.undo(),
+                Command::Redo => self.redo(),
+                Command::ReadOnly(false) if self.load_error.is_some() => {
+                    self.report(Err(
+                        "Changes stay off while a save file does not load".into()
+                    ));
+                }
+                Command::ReadOnly(on) => {
+                    self.set_readonly(on);
+                    let state = if on { "on" } else { "off" };
+                    self.report(Ok(format!("Read-only mode {}", state)));
+                }
+                // the window is opened in `call_on_app`
+                Command::Help => {}
+                Command::Quit => self.save_or_report(),
                 Command::MonthNext => self.sift_forward(),
                 Command::MonthPrev => self.sift_backward(),
                 Command::Blank => {}
@@ -242,3 +1786,420 @@ impl App {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::habit::{GoalPeriod, Habit};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static BOARDS: AtomicUsize = AtomicUsize::new(0);
+
+    // an app whose save files stay out of the real data directory,
+    // each on a profile of its own so tests never share a file
+    fn board(habits: Vec<Box<dyn HabitWrapper>>) -> App {
+        let dir = std::env::temp_dir().join("dijo-tests");
+        utils::set_data_dir(dir.to_str().unwrap());
+        let n = BOARDS.fetch_add(1, Ordering::SeqCst);
+        let mut app = App::with_profile(Some(format!("test{}-{}", std::process::id(), n)));
+        for h in habits {
+            app.add_habit(h);
+        }
+        app
+    }
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd(2026, 3, d)
+    }
+
+    #[test]
+    fn new_goal_applies_to_existing_entries() {
+        let mut count = Count::new("water", 3, false);
+        count.insert_entry(day(10), 2);
+        let mut app = board(vec![Box::new(count)]);
+        app.parse_command(Command::from_string("goal water 5"));
+        assert_eq!(app.habits[0].goal(), 5);
+        assert_eq!(app.habits[0].remaining(day(10)), 3);
+        app.parse_command(Command::from_string("goal water 0"));
+        assert_eq!(app.habits[0].goal(), 5);
+    }
+
+    #[test]
+    fn fill_sets_every_day_of_the_range() {
+        let mut app = board(vec![Box::new(Count::new("water", 3, false))]);
+        assert!(app.fill("water", day(2), day(6), "4").is_ok());
+        for d in 2..=6 {
+            assert_eq!(app.habits[0].raw_entry(day(d)), Some("4".to_owned()));
+        }
+        assert_eq!(app.habits[0].tracked_dates().len(), 5);
+        assert!(app.fill("water", day(2), day(6), "x").is_err());
+    }
+
+    #[test]
+    fn failed_save_keeps_the_work_unsaved() {
+        let mut app = board(vec![Box::new(Count::new("water", 3, false))]);
+        let file = utils::habit_file(app.profile.as_deref());
+        // a directory in the way of the save file cannot be
+        // renamed over
+        fs::create_dir_all(file.join("blocker")).unwrap();
+        app.mark_dirty();
+
+        assert!(app.save_state().is_err());
+        assert!(app.has_unsaved_work());
+        assert!(!file.with_extension("json.tmp").exists());
+        app.save_or_report();
+        assert_eq!(app.message.kind(), MessageKind::Error);
+
+        fs::remove_dir_all(&file).unwrap();
+        assert!(app.save_state().is_ok());
+        assert!(!app.has_unsaved_work());
+        assert_eq!(read_habits(file).0.len(), 1);
+    }
+
+    #[test]
+    fn loop_import_creates_and_merges_habits() {
+        let mut meditate = Bit::new("Meditate", false);
+        meditate.insert_entry(day(1), true.into());
+        let mut app = board(vec![Box::new(meditate)]);
+        let path = std::env::temp_dir().join(format!("dijo-loop-{}.csv", std::process::id()));
+        fs::write(&path, "Date,Meditate,Pushups,\n2026-03-02,2,25000,\n").unwrap();
+        let result = app.import_loop(path.to_str().unwrap());
+        fs::remove_file(&path);
+
+        assert_eq!(
+            result,
+            Ok("Imported 1 new and 1 existing habits".to_owned())
+        );
+        assert_eq!(app.habits.len(), 2);
+        assert_eq!(app.habits[0].tracked_dates().len(), 2);
+        assert_eq!(app.habits[1].name(), "Pushups");
+        assert_eq!(app.habits[1].raw_entry(day(2)), Some("25".to_owned()));
+    }
+
+    #[test]
+    fn merge_adds_up_overlapping_days() {
+        let mut source = Count::new("pushups am", 10, false);
+        source.insert_entry(day(1), 5);
+        source.insert_entry(day(2), 3);
+        let mut target = Count::new("pushups", 10, false);
+        target.insert_entry(day(2), 4);
+        target.insert_entry(day(3), 6);
+        let mut app = board(vec![Box::new(source), Box::new(target)]);
+
+        assert!(app.merge("pushups am", "pushups").is_ok());
+        assert_eq!(app.habits.len(), 1);
+        let h = &app.habits[0];
+        assert_eq!(h.raw_entry(day(1)), Some("5".to_owned()));
+        assert_eq!(h.raw_entry(day(2)), Some("7".to_owned()));
+        assert_eq!(h.raw_entry(day(3)), Some("6".to_owned()));
+    }
+
+    #[test]
+    fn merge_refuses_other_kinds() {
+        let mut app = board(vec![
+            Box::new(Count::new("pushups", 10, false)),
+            Box::new(Bit::new("walk", false)),
+        ]);
+        assert!(app.merge("pushups", "walk").is_err());
+        assert_eq!(app.habits.len(), 2);
+    }
+
+    #[test]
+    fn journal_rebuilds_renamed_converted_and_imported_habits() {
+        let mut app = board(vec![
+            Box::new(Count::new("water", 3, false)),
+            Box::new(Count::new("pages", 10, false)),
+        ]);
+        app.set_entry("water", day(1), "2").unwrap();
+        app.rename("water", "drink").unwrap();
+        app.set_entry("drink", day(2), "3").unwrap();
+        app.convert(0, None).unwrap();
+
+        let csv = std::env::temp_dir().join(format!("dijo-journal-{}.csv", std::process::id()));
+        fs::write(&csv, "date,value\n2026-03-04,12\n").unwrap();
+        app.import_csv(1, csv.to_str().unwrap()).unwrap();
+        fs::remove_file(&csv).unwrap();
+
+        let journal = utils::journal_file(app.profile.as_deref());
+        let mut rebuilt: Vec<Box<dyn HabitWrapper>> = vec![
+            Box::new(Bit::new("drink", false)),
+            Box::new(Count::new("pages", 10, false)),
+        ];
+        journal::replay(&mut rebuilt, &fs::read_to_string(&journal).unwrap());
+        fs::remove_file(&journal).unwrap();
+        for (a, b) in app.habits.iter().zip(rebuilt.iter()) {
+            for d in 1..=4 {
+                assert_eq!(a.raw_entry(day(d)), b.raw_entry(day(d)), "{}", a.name());
+            }
+        }
+        assert_eq!(rebuilt[0].raw_entry(day(1)), Some("0".to_owned()));
+        assert_eq!(rebuilt[0].raw_entry(day(2)), Some("1".to_owned()));
+        assert_eq!(rebuilt[1].raw_entry(day(4)), Some("12".to_owned()));
+    }
+
+    #[test]
+    fn new_habits_need_a_name_of_their_own() {
+        let mut app = board(vec![]);
+        app.parse_command(Command::from_string("new count drink water 8"));
+        app.parse_command(Command::from_string("new bit walk"));
+        assert_eq!(app.habits.len(), 2);
+        assert_eq!(app.habits[0].kind(), "Count");
+        assert_eq!(app.habits[0].goal(), 8);
+        assert_eq!(app.habits[1].kind(), "Bit");
+
+        app.parse_command(Command::from_string("new count walk 3"));
+        assert_eq!(app.habits.len(), 2);
+        assert_eq!(app.message.kind(), MessageKind::Error);
+    }
+
+    #[test]
+    fn dry_run_of_fill_changes_nothing() {
+        let mut count = Count::new("water", 3, false);
+        count.insert_entry(day(3), 1);
+        let mut app = board(vec![Box::new(count)]);
+        app.parse_command(Command::from_string(
+            "fill water 2026-03-02 2026-03-06 4 --dry-run",
+        ));
+        assert_eq!(app.habits[0].tracked_dates(), vec![day(3)]);
+        assert_eq!(app.habits[0].raw_entry(day(3)), Some("1".to_owned()));
+        assert!(!app.dirty);
+        assert_eq!(
+            app.message.contents(),
+            "Would create 4 and overwrite 1 entries of `water`"
+        );
+    }
+
+    #[test]
+    fn copy_previous_repeats_yesterday() {
+        let mut count = Count::new("water", 3, false);
+        count.insert_entry(day(9), 2);
+        let mut app = board(vec![Box::new(count)]);
+        assert!(app.copy_previous(0, day(10)).is_ok());
+        assert_eq!(app.habits[0].raw_entry(day(10)), Some("2".to_owned()));
+        assert!(app.copy_previous(0, day(12)).is_ok());
+        assert_eq!(app.habits[0].raw_entry(day(12)), None);
+    }
+
+    #[test]
+    fn copy_previous_leaves_frozen_days_alone() {
+        let mut count = Count::new("water", 3, false);
+        count.insert_entry(day(9), 2);
+        let mut app = board(vec![Box::new(count)]);
+        app.habits[0].freeze(day(10));
+        assert!(app.copy_previous(0, day(10)).is_err());
+        assert_eq!(app.habits[0].raw_entry(day(10)), None);
+        app.set_readonly(true);
+        assert!(app.copy_previous(0, day(11)).is_err());
+    }
+
+    #[test]
+    fn set_overwrites_a_single_day() {
+        let mut count = Count::new("water", 3, false);
+        count.insert_entry(day(9), 1);
+        let mut app = board(vec![Box::new(count), Box::new(Bit::new("walk", false))]);
+        app.parse_command(Command::from_string("set water 2026-03-09 4"));
+        app.parse_command(Command::from_string("set walk 2026-03-09 true"));
+        assert_eq!(app.habits[0].raw_entry(day(9)), Some("4".to_owned()));
+        assert_eq!(app.habits[0].raw_entry(day(10)), None);
+        assert_eq!(app.habits[1].raw_entry(day(9)), Some("1".to_owned()));
+
+        let bad = Command::from_string("set water 2024-13-01 2");
+        assert!(bad.is_err());
+        app.parse_command(bad);
+        assert_eq!(app.message.kind(), MessageKind::Error);
+        assert_eq!(app.habits[0].total(), 4);
+    }
+
+    #[test]
+    fn complete_today_leaves_nothing_remaining() {
+        let today = utils::today();
+        let mut water = Count::new("water", 3, false);
+        water.insert_entry(today, 1);
+        let mut app = board(vec![
+            Box::new(water),
+            Box::new(Count::new("pages", 20, false)),
+            Box::new(Bit::new("walk", false)),
+        ]);
+        app.complete_today();
+        for h in app.habits.iter() {
+            assert_eq!(h.remaining(today), 0, "{}", h.name());
+        }
+        app.undo();
+        assert_eq!(app.habits[0].raw_entry(today), Some("1".to_owned()));
+        assert_eq!(app.habits[1].raw_entry(today), None);
+        assert_eq!(app.habits[2].raw_entry(today), None);
+    }
+
+    #[test]
+    fn clone_copies_the_goal_but_no_entries() {
+        let mut water = Count::new("water", 8, false);
+        Habit::set_step(&mut water, 2);
+        water.insert_entry(day(3), 4);
+        let mut app = board(vec![Box::new(water)]);
+        app.parse_command(Command::from_string("clone water tea"));
+        assert_eq!(app.habits.len(), 2);
+        let tea = &app.habits[1];
+        assert_eq!(tea.name(), "tea");
+        assert_eq!(tea.kind(), "Count");
+        assert_eq!(tea.goal(), 8);
+        assert!(tea.tracked_dates().is_empty());
+        assert_eq!(app.habits[0].total(), 4);
+
+        app.parse_command(Command::from_string("clone tea water"));
+        assert_eq!(app.message.kind(), MessageKind::Error);
+        assert_eq!(app.habits.len(), 2);
+    }
+
+    #[test]
+    fn truncated_save_is_copied_aside() {
+        let dir = std::env::temp_dir().join(format!("dijo-corrupt-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("habit_record.json");
+        let full =
+            save::to_json(&[Box::new(Count::new("water", 3, false)) as Box<dyn HabitWrapper>])
+                .unwrap();
+        fs::write(&file, &full[..full.len() / 2]).unwrap();
+
+        let (habits, warning) = read_habits(file.clone());
+        assert!(habits.is_empty());
+        assert!(warning.unwrap().contains("a copy is kept at"));
+        let backups = fs::read_dir(&dir)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name().to_string_lossy().contains(".json.corrupt-"))
+            .count();
+        assert_eq!(backups, 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn save_file_that_did_not_load_is_left_alone() {
+        utils::set_data_dir(std::env::temp_dir().join("dijo-tests").to_str().unwrap());
+        let profile = format!("test{}-broken", std::process::id());
+        let file = utils::habit_file(Some(&profile));
+        fs::create_dir_all(file.parent().unwrap()).unwrap();
+        let newer = r#"{"version": 99, "habits": []}"#;
+        fs::write(&file, newer).unwrap();
+
+        let mut app = App::load_state(Some(profile));
+        assert!(app.load_error().is_some());
+        assert!(app.is_readonly());
+        assert_eq!(app.message.kind(), MessageKind::Error);
+        app.parse_command(Command::from_string("new bit walk"));
+        app.parse_command(Command::from_string("readonly off"));
+        assert!(app.is_readonly());
+        assert!(app.habits.is_empty());
+        assert!(app.save_state().is_err());
+        app.parse_command(Command::from_string("q"));
+        assert_eq!(fs::read_to_string(&file).unwrap(), newer);
+
+        let name = file.file_stem().unwrap().to_string_lossy().into_owned();
+        for entry in fs::read_dir(file.parent().unwrap()).unwrap().flatten() {
+            if entry.file_name().to_string_lossy().starts_with(&name) {
+                fs::remove_file(entry.path()).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn reminders_list_what_is_left_today() {
+        let today = utils::today();
+        let mut water = Count::new("water", 3, false);
+        water.insert_entry(today, 1);
+        let mut walk = Bit::new("walk", false);
+        walk.insert_entry(today, true.into());
+        let mut old = Bit::new("old", false);
+        Habit::set_archived(&mut old, true);
+        let mut app = board(vec![
+            Box::new(water),
+            Box::new(walk),
+            Box::new(old),
+            Box::new(Bit::new("read", false)),
+        ]);
+        assert_eq!(
+            app.reminders(),
+            Some("water (2 left)\nread (1 left)".to_owned())
+        );
+        app.complete_today();
+        assert_eq!(app.reminders(), None);
+    }
+
+    #[test]
+    fn info_spans_the_first_to_the_last_entry() {
+        let mut water = Count::new("water", 3, false);
+        water.insert_entry(day(9), 3);
+        water.insert_entry(day(2), 1);
+        water.insert_entry(day(4), 2);
+        let app = board(vec![Box::new(water), Box::new(Bit::new("walk", false))]);
+        let info = app.info(0).unwrap();
+        assert!(info.starts_with(&format!(
+            "water: {} to {}, 3 days tracked",
+            utils::display_date(day(2)),
+            utils::display_date(day(9))
+        )));
+        assert!(info.ends_with("total 6"));
+        assert_eq!(app.info(1), Ok("walk: no data".to_owned()));
+    }
+
+    #[test]
+    fn milestones_fire_only_on_the_day_they_are_reached() {
+        let today = utils::today();
+        let fired = (1..=40)
+            .filter(|&n| {
+                let mut walk = Bit::new("walk", false);
+                for k in 1..n {
+                    walk.insert_entry(today - chrono::Duration::days(k), true.into());
+                }
+                let mut app = board(vec![Box::new(walk)]);
+                assert_eq!(app.new_milestone(0, false), None);
+                app.habits[0].insert_raw(today, "1");
+                assert_eq!(app.new_milestone(0, true), None);
+                app.new_milestone(0, false) == Some(n as u32)
+            })
+            .map(|n| n as u32)
+            .collect::<Vec<_>>();
+        let expected = CONFIGURATION
+            .milestones
+            .iter()
+            .cloned()
+            .filter(|&m| m <= 40)
+            .collect::<Vec<_>>();
+        assert_eq!(fired, expected);
+    }
+
+    #[test]
+    fn convert_count_to_bit_follows_the_goal() {
+        let mut weekly = Count::new("runs", 2, false);
+        weekly.set_goal_period(GoalPeriod::Weekly);
+        weekly.insert_entry(day(2), 1);
+        weekly.insert_entry(day(4), 1);
+        weekly.insert_entry(day(9), 1);
+        let mut logged = Count::new("coffee", 0, false);
+        logged.insert_entry(day(2), 0);
+        logged.insert_entry(day(3), 2);
+        let mut app = board(vec![Box::new(weekly), Box::new(logged)]);
+
+        assert!(app.convert(0, None).is_ok());
+        assert_eq!(app.habits[0].kind(), "Bit");
+        assert_eq!(app.habits[0].raw_entry(day(4)), Some("1".to_owned()));
+        assert_eq!(app.habits[0].raw_entry(day(9)), Some("0".to_owned()));
+
+        assert!(app.convert(1, None).is_ok());
+        assert_eq!(app.habits[1].raw_entry(day(2)), Some("0".to_owned()));
+        assert_eq!(app.habits[1].raw_entry(day(3)), Some("1".to_owned()));
+    }
+
+    #[test]
+    fn convert_bit_to_count_keeps_done_days() {
+        let mut bit = Bit::new("walk", false);
+        bit.insert_entry(day(2), true.into());
+        bit.insert_entry(day(3), false.into());
+        let mut app = board(vec![Box::new(bit)]);
+        assert!(app.convert(0, Some(3)).is_ok());
+        assert_eq!(app.habits[0].kind(), "Count");
+        assert_eq!(app.habits[0].goal(), 3);
+        assert_eq!(app.habits[0].raw_entry(day(2)), Some("1".to_owned()));
+        assert_eq!(app.habits[0].raw_entry(day(3)), None);
+        assert!(app.convert(0, Some(3)).is_err());
+    }
+}
@@ -1,35 +1,36 @@
-use std::f64;
 use std::fs::File;
 use std::io::prelude::*;
-use std::path::PathBuf;
 
 use cursive::direction::{Absolute, Direction};
-use cursive::event::{Event, EventResult, Key};
+use cursive::event::{Event, EventResult};
 use cursive::theme::Color;
 use cursive::view::View;
 use cursive::{Printer, Vec2};
 use notify::DebouncedEvent;
 
-use crate::app::{App, MessageKind};
-use crate::habit::{HabitWrapper, ViewMode};
+use crate::app::impl_self::{read_habits, READONLY};
+use crate::app::{App, MessageKind, UndoAction};
+use crate::command::help_text;
+use crate::habit::ViewMode;
+use crate::keymap;
 use crate::utils;
+use crate::views::{
+    open_delete_window, open_detail_window, open_note_window, open_report_window,
+    open_stats_window, open_value_window,
+};
 use crate::CONFIGURATION;
 
 impl View for App {
     fn draw(&self, printer: &Printer) {
-        let grid_width = CONFIGURATION.grid_width;
-        let view_width = CONFIGURATION.view_width;
-        let view_height = CONFIGURATION.view_height;
-        let mut offset = Vec2::zero();
-        for (idx, habit) in self.habits.iter().enumerate() {
-            if idx >= grid_width && idx % grid_width == 0 {
-                offset = offset.map_y(|y| y + view_height).map_x(|_| 0);
-            }
+        for (idx, offset) in self.grid_layout().0 {
+            let habit = &self.habits[idx];
             habit.draw(&printer.offset(offset).focused(self.focus == idx));
-            offset = offset.map_x(|x| x + view_width + 2);
+            if self.is_selected(idx) {
+                printer.offset(offset).print((0, 0), "*");
+            }
         }
 
-        offset = offset.map_x(|_| 0).map_y(|_| self.max_size().y - 2);
+        let mut offset = Vec2::new(0, self.max_size().y - 2);
 
         let status = self.status();
         printer.print(offset, &status.0); // left status
@@ -44,20 +45,10 @@ impl View for App {
         });
     }
 
-    fn required_size(&mut self, _: Vec2) -> Vec2 {
-        let grid_width = CONFIGURATION.grid_width;
-        let view_width = CONFIGURATION.view_width;
-        let view_height = CONFIGURATION.view_height;
-        let width = grid_width * (view_width + 2);
-        let height = {
-            if self.habits.len() > 0 {
-                (view_height as f64 * (self.habits.len() as f64 / grid_width as f64).ceil())
-                    as usize
-            } else {
-                0
-            }
-        };
-        Vec2::new(width, height + 2)
+    fn required_size(&mut self, constraint: Vec2) -> Vec2 {
+        let grid = self.grid_layout().1;
+        let width = (CONFIGURATION.grid_width * (CONFIGURATION.view_width + 2)).max(grid.x);
+        Vec2::new(width.min(constraint.x), (grid.y + 2).min(constraint.y))
     }
 
     fn take_focus(&mut self, _: Direction) -> bool {
@@ -67,78 +58,132 @@ impl View for App {
     fn on_event(&mut self, e: Event) -> EventResult {
         match self.file_event_recv.try_recv() {
             Ok(DebouncedEvent::Write(_)) => {
-                let read_from_file = |file: PathBuf| -> Vec<Box<dyn HabitWrapper>> {
-                    if let Ok(ref mut f) = File::open(file) {
-                        let mut j = String::new();
-                        f.read_to_string(&mut j);
-                        return serde_json::from_str(&j).unwrap();
-                    } else {
-                        return Vec::new();
+                let file = utils::auto_habit_file(self.profile.as_deref());
+                match read_habits(file) {
+                    // keep what is on the board rather than drop it
+                    (_, Some(warning)) => self.report(Err(warning)),
+                    (auto, None) => {
+                        self.habits.retain(|x| !x.is_auto());
+                        self.habits.extend(auto);
+                        let len = self.habits.len();
+                        self.selected.retain(|&i| i < len);
                     }
-                };
-                let auto = read_from_file(utils::auto_habit_file());
-                self.habits.retain(|x| !x.is_auto());
-                self.habits.extend(auto);
+                }
             }
             _ => {}
         };
-        if self.habits.is_empty() {
+
+        // a `/` jump takes every key until it is done
+        if self.jump_event(&e) {
+            return EventResult::Consumed(None);
+        }
+        let action = match keymap::action_for(&e) {
+            Some(action) => action,
+            None if e == Event::Char('w') => {
+                // helper bind to test write to file
+                let j = serde_json::to_string_pretty(&self.habits).unwrap();
+                let mut file = File::create("foo.txt").unwrap();
+                file.write_all(j.as_bytes()).unwrap();
+                return EventResult::Consumed(None);
+            }
+            None => return EventResult::Ignored,
+        };
+        if self.is_readonly() && keymap::edits(action) {
+            self.report(Err(READONLY.into()));
+            return EventResult::Consumed(None);
+        }
+        if self.visible().is_empty() && action != "archived" {
             return EventResult::Ignored;
         }
-        match e {
-            Event::Key(Key::Right) | Event::Key(Key::Tab) | Event::Char('l') => {
+        match action {
+            "focus_right" => {
                 self.set_focus(Absolute::Right);
                 return EventResult::Consumed(None);
             }
-            Event::Key(Key::Left) | Event::Shift(Key::Tab) | Event::Char('h') => {
+            "focus_left" => {
                 self.set_focus(Absolute::Left);
                 return EventResult::Consumed(None);
             }
-            Event::Key(Key::Up) | Event::Char('k') => {
+            "focus_up" => {
                 self.set_focus(Absolute::Up);
                 return EventResult::Consumed(None);
             }
-            Event::Key(Key::Down) | Event::Char('j') => {
+            "focus_down" => {
                 self.set_focus(Absolute::Down);
                 return EventResult::Consumed(None);
             }
-            Event::Char('d') => {
+            "move_up" => {
+                let result = self.move_habit(self.focus, true);
+                if result.is_ok() {
+                    self.mark_dirty();
+                }
+                self.report(result);
+                EventResult::Consumed(None)
+            }
+            "move_down" => {
+                let result = self.move_habit(self.focus, false);
+                if result.is_ok() {
+                    self.mark_dirty();
+                }
+                self.report(result);
+                EventResult::Consumed(None)
+            }
+            "delete" => {
                 if self.habits.is_empty() {
                     return EventResult::Consumed(None);
                 }
-                self.habits.remove(self.focus);
-                self.focus = self.focus.checked_sub(1).unwrap_or(0);
-                return EventResult::Consumed(None);
+                // asks first, just as `:delete` does
+                let name = self.habits[self.focus].name();
+                match self.delete_prompt(Some(&name)) {
+                    Ok((names, days)) => {
+                        EventResult::with_cb(move |s| open_delete_window(s, names.clone(), days))
+                    }
+                    Err(e) => {
+                        self.report(Err(e));
+                        EventResult::Consumed(None)
+                    }
+                }
             }
-            Event::Char('w') => {
-                // helper bind to test write to file
-                let j = serde_json::to_string_pretty(&self.habits).unwrap();
-                let mut file = File::create("foo.txt").unwrap();
-                file.write_all(j.as_bytes()).unwrap();
+            "jump" => {
+                self.start_jump();
+                EventResult::Consumed(None)
+            }
+            "help" => EventResult::with_cb(|s| open_report_window(s, "Help", help_text())),
+            "archived" => {
+                self.toggle_archived_view();
                 return EventResult::Consumed(None);
             }
-            Event::Char('q') => {
-                self.save_state();
+            "quit" => {
+                // unsaved work keeps the board open until it can
+                // be written
+                if let Err(e) = self.save_state() {
+                    if self.dirty {
+                        self.report(Err(e));
+                        return EventResult::Consumed(None);
+                    }
+                }
                 return EventResult::with_cb(|s| s.quit());
             }
-            Event::Char('v') => {
+            "cycle_view" => {
                 if self.habits.is_empty() {
                     return EventResult::Consumed(None);
                 }
-                if self.habits[self.focus].view_mode() == ViewMode::Week {
-                    self.set_mode(ViewMode::Day)
-                } else {
-                    self.set_mode(ViewMode::Week)
-                }
+                let mode = self.habits[self.focus].view_mode().next();
+                self.set_mode(mode);
                 return EventResult::Consumed(None);
             }
-            Event::Char('V') => {
+            "week_view" => {
                 for habit in self.habits.iter_mut() {
                     habit.set_view_mode(ViewMode::Week);
                 }
                 return EventResult::Consumed(None);
             }
-            Event::Key(Key::Esc) => {
+            "select" => {
+                self.toggle_selected(self.focus);
+                EventResult::Consumed(None)
+            }
+            "clear_selection" => {
+                self.clear_selection();
                 for habit in self.habits.iter_mut() {
                     habit.set_view_mode(ViewMode::Day);
                 }
@@ -148,35 +193,190 @@ impl View for App {
             /* We want sifting to be an app level function,
              * that later trickles down into each habit
              * */
-            Event::Char(']') => {
+            "month_next" => {
                 self.sift_forward();
                 return EventResult::Consumed(None);
             }
-            Event::Char('[') => {
+            "month_prev" => {
                 self.sift_backward();
                 return EventResult::Consumed(None);
             }
-            Event::Char('}') => {
+            "cursor_back" => {
+                self.move_cursor(-1);
+                EventResult::Consumed(None)
+            }
+            "cursor_forward" => {
+                self.move_cursor(1);
+                EventResult::Consumed(None)
+            }
+            "today" => {
+                self.set_cursor(utils::today());
                 self.set_view_month_offset(0);
                 return EventResult::Consumed(None);
             }
-            Event::CtrlChar('l') => {
+            // anything but a yes/no habit takes an exact value,
+            // a `Bit` still toggles below
+            "value"
+                if self.habits[self.focus].kind() != "Bit"
+                    && !self.habits[self.focus].is_auto() =>
+            {
+                let date = self.cursor();
+                let habit = &self.habits[self.focus];
+                let name = habit.name();
+                let value = habit.raw_entry(date).unwrap_or_default();
+                EventResult::with_cb(move |s| {
+                    open_value_window(s, name.clone(), date, value.clone())
+                })
+            }
+            "complete_today" => {
+                let was_done = self.all_done_today();
+                let message = self.complete_today();
+                self.report(Ok(message));
+                if !was_done && self.all_done_today() {
+                    self.celebrate();
+                }
+                EventResult::Consumed(None)
+            }
+            "copy_previous" => {
+                if !self.habits[self.focus].is_auto() {
+                    let result = self.copy_previous(self.focus, self.cursor());
+                    self.report(result);
+                }
+                EventResult::Consumed(None)
+            }
+            "note" => {
+                let today = self.cursor();
+                let habit = &self.habits[self.focus];
+                let name = habit.name();
+                let note = habit.get_note(today).cloned().unwrap_or_default();
+                EventResult::with_cb(move |s| {
+                    open_note_window(s, name.clone(), today, note.clone())
+                })
+            }
+            "detail" => {
+                let habit = serde_json::to_value(&self.habits[self.focus]).unwrap();
+                EventResult::with_cb(move |s| open_detail_window(s, habit.clone()))
+            }
+            "stats" => {
+                let offset = self.view_month_offset;
+                let rows = self.month_stats(offset);
+                EventResult::with_cb(move |s| open_stats_window(s, offset, rows.clone()))
+            }
+            "undo" => {
+                self.undo();
+                EventResult::Consumed(None)
+            }
+            "redo" => {
+                self.redo();
+                EventResult::Consumed(None)
+            }
+            "clear_message" => {
                 self.message.clear();
                 self.message.set_kind(MessageKind::Info);
                 return EventResult::Consumed(None);
             }
 
             /* Every keybind that is not caught by App trickles
-             * down to the focused habit. We sift back to today
-             * before performing any action, "refocusing" the cursor
+             * down to the focused habit. We sift back to the
+             * cursor before performing any action, "refocusing"
+             * the cursor
              * */
-            _ => {
+            "increment" | "value" | "decrement" | "clear" | "freeze" => {
                 if self.habits.is_empty() {
                     return EventResult::Ignored;
                 }
-                self.set_view_month_offset(0);
-                self.habits[self.focus].on_event(e)
+                // the habit knows the action by its own key
+                let e = match keymap::default_key(action) {
+                    Some(e) => e,
+                    None => return EventResult::Ignored,
+                };
+                self.set_view_month_offset(utils::month_offset(self.cursor()));
+
+                let was_done = self.all_done_today();
+                let today = self.cursor();
+                let habit = &mut self.habits[self.focus];
+                let was_reached = today != utils::today() || habit.reached_goal(today);
+                let before = habit.raw_entry(today);
+                let result = habit.on_event(e);
+                let action = UndoAction {
+                    habit: habit.name(),
+                    date: today,
+                    before,
+                    after: habit.raw_entry(today),
+                };
+                self.record_change(action);
+                if !was_done && self.all_done_today() {
+                    self.celebrate();
+                }
+                if let Some(streak) = self.new_milestone(self.focus, was_reached) {
+                    let name = self.habits[self.focus].name();
+                    self.report(Ok(format!("{} days of `{}` in a row!", streak, name)));
+                }
+                result
             }
+            _ => EventResult::Ignored,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::habit::Count;
+    use cursive::event::Key;
+
+    fn board() -> App {
+        let dir = std::env::temp_dir().join("dijo-tests");
+        utils::set_data_dir(dir.to_str().unwrap());
+        let mut app = App::new();
+        app.add_habit(Box::new(Count::new("water", 3, false)));
+        app
+    }
+
+    #[test]
+    fn modify_is_a_no_op_while_read_only() {
+        let today = utils::today();
+        let mut app = board();
+        app.set_readonly(true);
+        app.on_event(Event::Char('n'));
+        app.on_event(Event::Key(Key::Enter));
+        assert_eq!(app.habits[0].raw_entry(today), None);
+        assert_eq!(app.message.kind(), MessageKind::Error);
+
+        app.set_readonly(false);
+        app.on_event(Event::Char('n'));
+        assert_eq!(app.habits[0].amount(today), 1);
+    }
+
+    #[test]
+    fn only_changes_leave_unsaved_work() {
+        let mut app = board();
+        app.on_event(Event::Char('p'));
+        app.on_event(Event::Char('u'));
+        assert!(!app.dirty);
+        app.on_event(Event::Char('n'));
+        assert!(app.dirty);
+    }
+
+    #[test]
+    fn delete_key_asks_before_deleting() {
+        let mut app = board();
+        let asked = app.on_event(Event::Char('d'));
+        assert!(matches!(asked, EventResult::Consumed(Some(_))));
+        assert_eq!(app.habits.len(), 1);
+
+        app.set_readonly(true);
+        let refused = app.on_event(Event::Char('d'));
+        assert!(matches!(refused, EventResult::Consumed(None)));
+        assert_eq!(app.message.kind(), MessageKind::Error);
+    }
+
+    #[test]
+    fn board_fits_a_tiny_terminal() {
+        let mut app = board();
+        for &(x, y) in &[(0, 0), (1, 1), (5, 3), (2, 40), (40, 2)] {
+            let size = app.required_size(Vec2::new(x, y));
+            assert!(size.x <= x && size.y <= y, "{}x{}", x, y);
         }
     }
 }
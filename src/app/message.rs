@@ -1,6 +1,6 @@
 use cursive::theme::{BaseColor, Color};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum MessageKind {
     Error,
     Info,
@@ -1,16 +1,23 @@
+use std::collections::HashSet;
 use std::default::Default;
 use std::sync::mpsc::Receiver;
+use std::time::Instant;
 
+use chrono::NaiveDate;
 use notify::{DebouncedEvent, RecommendedWatcher};
 
 use crate::habit::HabitWrapper;
 
+mod history;
 mod impl_self;
 mod impl_view;
 mod message;
+mod undo;
 
 pub struct StatusLine(String, String);
+pub use history::CommandHistory;
 pub use message::{Message, MessageKind};
+pub use undo::{UndoAction, UndoStack};
 
 pub struct App {
     // holds app data
@@ -19,8 +26,33 @@ pub struct App {
     _file_watcher: RecommendedWatcher,
     file_event_recv: Receiver<DebouncedEvent>,
     focus: usize,
+    // indices of habits marked for commands that are given
+    // no habit
+    selected: HashSet<usize>,
+    show_archived: bool,
+    filter: Option<String>,
     view_month_offset: u32,
+    cursor: NaiveDate,
     message: Message,
+    undo_stack: UndoStack,
+    history: CommandHistory,
+    // prefix typed after `/` along with the habit that was
+    // focused before, `None` when not jumping
+    jump: Option<(String, usize)>,
+
+    // separate set of save files, `None` is the default one
+    profile: Option<String>,
+
+    // refuses every change to habits while set
+    readonly: bool,
+
+    // why a save file could not be loaded. nothing is written
+    // while set, an empty board would replace what was there
+    load_error: Option<String>,
+
+    // changes that have not been written out yet
+    dirty: bool,
+    last_save: Instant,
 }
 
 impl Default for App {
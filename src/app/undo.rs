@@ -0,0 +1,52 @@
+use std::collections::VecDeque;
+
+use chrono::NaiveDate;
+
+const UNDO_DEPTH: usize = 100;
+
+// a single change to one day of one habit, values are kept in
+// their raw form so that any kind of habit can be restored
+#[derive(Debug, Clone)]
+pub struct UndoAction {
+    pub habit: String,
+    pub date: NaiveDate,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+// actions that were made together and are undone together
+pub type UndoGroup = Vec<UndoAction>;
+
+#[derive(Default)]
+pub struct UndoStack {
+    undo: VecDeque<UndoGroup>,
+    redo: Vec<UndoGroup>,
+}
+
+impl UndoStack {
+    pub fn push(&mut self, action: UndoAction) {
+        self.push_group(vec![action]);
+    }
+
+    pub fn push_group(&mut self, group: UndoGroup) {
+        if self.undo.len() == UNDO_DEPTH {
+            self.undo.pop_front();
+        }
+        self.undo.push_back(group);
+        self.redo.clear();
+    }
+
+    // the group to revert, it is moved onto the redo stack
+    pub fn undo(&mut self) -> Option<UndoGroup> {
+        let group = self.undo.pop_back()?;
+        self.redo.push(group.clone());
+        Some(group)
+    }
+
+    // the group to apply again, it is moved back onto the undo stack
+    pub fn redo(&mut self) -> Option<UndoGroup> {
+        let group = self.redo.pop()?;
+        self.undo.push_back(group.clone());
+        Some(group)
+    }
+}
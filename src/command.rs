@@ -1,13 +1,21 @@
 use std::fmt;
 
+use cursive::event::Key;
 use cursive::theme::{BaseColor, Color, ColorStyle};
-use cursive::view::Resizable;
-use cursive::views::{EditView, LinearLayout, TextView};
+use cursive::view::{Nameable, Resizable};
+use cursive::views::{EditView, LinearLayout, OnEventView, TextView};
 use cursive::Cursive;
 
-use crate::{app::App, CONFIGURATION};
+use chrono::{NaiveDate, Weekday};
+
+use crate::app::App;
+use crate::habit::{GoalPeriod, Schedule, ViewMode, MAX_RECENT_DAYS, MAX_TASKS, RECENT_DAYS};
+use crate::keymap::{self, ACTIONS};
+use crate::views::{open_delete_window, open_report_window};
+use crate::CONFIGURATION;
 
 pub fn open_command_window(s: &mut Cursive) {
+    s.call_on_name("Main", |view: &mut App| view.history().reset());
     let command_window = EditView::new()
         .filler(" ")
         .on_submit(call_on_app)
@@ -15,6 +23,10 @@ pub fn open_command_window(s: &mut Cursive) {
             Color::Dark(BaseColor::Black),
             Color::Dark(BaseColor::White),
         ))
+        .with_name("Command");
+    let command_window = OnEventView::new(command_window)
+        .on_pre_event(Key::Up, |s| recall_command(s, true))
+        .on_pre_event(Key::Down, |s| recall_command(s, false))
         .fixed_width(CONFIGURATION.view_width * CONFIGURATION.grid_width);
     s.call_on_name("Frame", |view: &mut LinearLayout| {
         let mut commandline = LinearLayout::horizontal()
@@ -26,6 +38,25 @@ pub fn open_command_window(s: &mut Cursive) {
     });
 }
 
+// replace the contents of the command bar with an older
+// command, or a newer one
+fn recall_command(s: &mut Cursive, older: bool) {
+    let recalled = s
+        .call_on_name("Main", |view: &mut App| {
+            if older {
+                view.history().prev()
+            } else {
+                view.history().next()
+            }
+        })
+        .flatten();
+    if let Some(command) = recalled {
+        s.call_on_name("Command", |view: &mut EditView| {
+            view.set_content(command);
+        });
+    }
+}
+
 fn call_on_app(s: &mut Cursive, input: &str) {
     // things to do after recieving the command
     // 1. parse the command
@@ -34,6 +65,7 @@ fn call_on_app(s: &mut Cursive, input: &str) {
     // 4. handle quit command
     s.call_on_name("Main", |view: &mut App| {
         let cmd = Command::from_string(input);
+        view.history().push(input);
         view.clear_message();
         view.parse_command(cmd);
     });
@@ -46,28 +78,523 @@ fn call_on_app(s: &mut Cursive, input: &str) {
     // our main cursive object, has to be parsed again
     // here
     // TODO: fix this somehow
-    if let Ok(Command::Quit) = Command::from_string(input) {
-        s.quit();
+    match Command::from_string(input) {
+        Ok(Command::Quit) => {
+            if s.call_on_name("Main", |view: &mut App| !view.has_unsaved_work()) == Some(true) {
+                s.quit();
+            }
+        }
+        Ok(Command::Help) => open_report_window(s, "Help", help_text()),
+        Ok(Command::ReportStreaks(None)) => {
+            if let Some(report) = s.call_on_name("Main", |view: &mut App| view.streak_report()) {
+                open_report_window(s, "Streaks", report);
+            }
+        }
+        Ok(Command::List(json)) => {
+            let list = s.call_on_name("Main", |view: &mut App| match view.list(json) {
+                Ok(list) => Some(list),
+                Err(e) => {
+                    view.report(Err(e));
+                    None
+                }
+            });
+            if let Some(list) = list.flatten() {
+                open_report_window(s, "Habits", list);
+            }
+        }
+        Ok(Command::Delete(name)) => {
+            let target = s
+                .call_on_name("Main", |view: &mut App| {
+                    view.delete_prompt(name.as_deref()).ok()
+                })
+                .flatten();
+            if let Some((names, days)) = target {
+                open_delete_window(s, names, days);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Resolution<'a> {
+    Exact(&'a str),
+    Ambiguous(Vec<&'a str>),
+    NotFound,
+}
+
+// find the habit `query` refers to, an exact name always wins,
+// followed by a unique prefix and then a unique substring, case
+// is ignored for the latter two
+pub fn resolve_habit<'a>(query: &str, names: &'a [String]) -> Resolution<'a> {
+    if let Some(n) = names.iter().find(|n| *n == query) {
+        return Resolution::Exact(n);
+    }
+    let query = query.to_lowercase();
+    let prefixed: Vec<&str> = names
+        .iter()
+        .filter(|n| n.to_lowercase().starts_with(&query))
+        .map(|n| n.as_str())
+        .collect();
+    if prefixed.len() == 1 {
+        return Resolution::Exact(prefixed[0]);
+    }
+    let mut candidates: Vec<&str> = names
+        .iter()
+        .filter(|n| n.to_lowercase().contains(&query))
+        .map(|n| n.as_str())
+        .collect();
+    match candidates.len() {
+        0 => Resolution::NotFound,
+        1 => Resolution::Exact(candidates.remove(0)),
+        _ => Resolution::Ambiguous(candidates),
+    }
+}
+
+// comparison used by `:find` to pick out days
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Comparison {
+    Greater,
+    Less,
+    GreaterEq,
+    LessEq,
+    Equal,
+}
+
+impl Comparison {
+    fn from_str(op: &str) -> Option<Self> {
+        match op {
+            ">" => Some(Comparison::Greater),
+            "<" => Some(Comparison::Less),
+            ">=" => Some(Comparison::GreaterEq),
+            "<=" => Some(Comparison::LessEq),
+            "=" | "==" => Some(Comparison::Equal),
+            _ => None,
+        }
+    }
+
+    pub fn holds(self, lhs: f64, rhs: f64) -> bool {
+        match self {
+            Comparison::Greater => lhs > rhs,
+            Comparison::Less => lhs < rhs,
+            Comparison::GreaterEq => lhs >= rhs,
+            Comparison::LessEq => lhs <= rhs,
+            Comparison::Equal => (lhs - rhs).abs() < f64::EPSILON,
+        }
     }
 }
 
-#[derive(PartialEq)]
+// order that `:sort` puts the board in, ties are broken by name
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SortKey {
+    Name,
+    // most left to do today first
+    Remaining,
+    // longest current streak first
+    Streak,
+}
+
+impl SortKey {
+    pub fn parse(key: &str) -> Option<Self> {
+        match key.trim() {
+            "name" => Some(SortKey::Name),
+            "remaining" => Some(SortKey::Remaining),
+            "streak" => Some(SortKey::Streak),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
 pub enum Command {
     Add(String, Option<u32>, bool),
+    AddFloat(String, f64, bool),
+    AddDuration(String, u32, bool),
+    AddPeriodic(String, u32, GoalPeriod, bool),
+    AddCapped(String, u32, bool),
+    AddChecklist(String, u32, bool),
+    NewCount(String, u32),
+    NewBit(String),
     MonthPrev,
     MonthNext,
-    Delete(String),
+    Delete(Option<String>),
+    ForceDelete(Option<String>),
     TrackUp(String),
     TrackDown(String),
+    ExportCsv(String, String),
+    ExportIcs(String, String),
+    ExportMatrix(String),
+    ExportJson(String),
+    ImportCsv(String, String),
+    ImportLoop(String),
+    Color(String, Option<String>),
+    Archive(Option<String>),
+    Unarchive(Option<String>),
+    Rename(String, String),
+    Clone(String, String),
+    Convert(String, Option<u32>),
+    Goal(String, u32),
+    Negative(String, bool),
+    Step(String, u32),
+    Target(String, Option<(u32, NaiveDate)>),
+    Rolling(String, Option<(u32, u32)>),
+    Merge(String, String),
+    Repair,
+    Profile(Option<String>),
+    // reports what the command would do instead of doing it
+    DryRun(Box<Command>),
+    Sort(SortKey),
+    Replay(String),
+    Insights(String),
+    Info(String),
+    List(bool),
+    ReportStreaks(Option<String>),
+    Tag(Option<String>, String),
+    Untag(Option<String>, String),
+    Filter(Option<String>),
+    Schedule(String, Schedule),
+    Fill(String, NaiveDate, NaiveDate, String),
+    Set(String, NaiveDate, String),
+    Glyphs(String, Option<char>, Option<char>),
+    Weekends(String, bool),
+    Pause(String, NaiveDate),
+    Resume(String),
+    Find(String, Comparison, f64),
+    FindClear,
+    View(ViewMode),
+    MoveUp(String),
+    MoveDown(String),
+    Undo,
+    Redo,
+    ReadOnly(bool),
+    Help,
     Quit,
     Blank,
 }
 
+pub struct CommandInfo {
+    // the first name is the one shown in `:help`
+    pub names: &'static [&'static str],
+    pub usage: &'static str,
+    pub about: &'static str,
+}
+
+// every command that `from_string` knows, anything missing
+// here is refused as unknown
+pub const COMMANDS: &[CommandInfo] = &[
+    CommandInfo {
+        names: &["add", "a"],
+        usage: "add <name> [goal | goal/day|week|month | <=cap | [tasks] | minutesm]",
+        about: "add a habit, the goal decides its kind",
+    },
+    CommandInfo {
+        names: &["add-auto", "aa"],
+        usage: "add-auto <name> [goal]",
+        about: "add a habit that only commands track",
+    },
+    CommandInfo {
+        names: &["new"],
+        usage: "new count <name...> <goal> | new bit <name...>",
+        about: "add a habit whose name has spaces",
+    },
+    CommandInfo {
+        names: &["delete", "d"],
+        usage: "delete [name]",
+        about: "delete a habit, or the selection, after asking",
+    },
+    CommandInfo {
+        names: &["delete!", "d!"],
+        usage: "delete! [name]",
+        about: "delete a habit, or the selection, without asking",
+    },
+    CommandInfo {
+        names: &["track-up", "tup"],
+        usage: "track-up <name>",
+        about: "track an auto habit once more today",
+    },
+    CommandInfo {
+        names: &["track-down", "tdown"],
+        usage: "track-down <name>",
+        about: "take back a track of an auto habit today",
+    },
+    CommandInfo {
+        names: &["set"],
+        usage: "set <name> <yyyy-mm-dd> <value>",
+        about: "set the value of a single day",
+    },
+    CommandInfo {
+        names: &["fill"],
+        usage: "fill <name> <from> <to> <value>",
+        about: "set every day of a range to one value",
+    },
+    CommandInfo {
+        names: &["goal"],
+        usage: "goal <name> <goal>",
+        about: "change the goal of a habit",
+    },
+    CommandInfo {
+        names: &["step"],
+        usage: "step <name> <n>",
+        about: "change how much a key press counts",
+    },
+    CommandInfo {
+        names: &["target"],
+        usage: "target <name> <total> <yyyy-mm-dd> | target <name> off",
+        about: "aim for a total by a deadline",
+    },
+    CommandInfo {
+        names: &["rolling"],
+        usage: "rolling <name> <n>/<m> | rolling <name> off",
+        about: "reach a yes/no goal n times in every m days",
+    },
+    CommandInfo {
+        names: &["negative"],
+        usage: "negative <name> on|off",
+        about: "let a count go below zero",
+    },
+    CommandInfo {
+        names: &["schedule"],
+        usage: "schedule <name> daily|weekdays|weekends|<days...>",
+        about: "pick the days a habit is due",
+    },
+    CommandInfo {
+        names: &["weekends"],
+        usage: "weekends <name> show|hide",
+        about: "leave weekends out of the day view",
+    },
+    CommandInfo {
+        names: &["pause"],
+        usage: "pause <name> <yyyy-mm-dd>",
+        about: "rest a habit from today until the given day",
+    },
+    CommandInfo {
+        names: &["resume"],
+        usage: "resume <name>",
+        about: "end the pause of a habit",
+    },
+    CommandInfo {
+        names: &["rename"],
+        usage: "rename <name> <new name>",
+        about: "rename a habit",
+    },
+    CommandInfo {
+        names: &["clone"],
+        usage: "clone <name> <new name>",
+        about: "add a habit set up like another, without its entries",
+    },
+    CommandInfo {
+        names: &["convert"],
+        usage: "convert <name> count <goal> | convert <name> bit",
+        about: "turn a yes/no habit into a count or back",
+    },
+    CommandInfo {
+        names: &["color"],
+        usage: "color <name> [color]",
+        about: "color a habit, or go back to the default",
+    },
+    CommandInfo {
+        names: &["glyph"],
+        usage: "glyph <name> [done] [missed]",
+        about: "characters drawn for a yes/no habit",
+    },
+    CommandInfo {
+        names: &["tag"],
+        usage: "tag [name] <tag>",
+        about: "tag a habit, or the selection",
+    },
+    CommandInfo {
+        names: &["untag"],
+        usage: "untag [name] <tag>",
+        about: "remove a tag from a habit, or the selection",
+    },
+    CommandInfo {
+        names: &["filter"],
+        usage: "filter <tag> | filter clear",
+        about: "only show habits with a tag",
+    },
+    CommandInfo {
+        names: &["archive"],
+        usage: "archive [name]",
+        about: "hide a habit, or the selection, from the board",
+    },
+    CommandInfo {
+        names: &["unarchive"],
+        usage: "unarchive [name]",
+        about: "bring back an archived habit",
+    },
+    CommandInfo {
+        names: &["move"],
+        usage: "move <name> up|down",
+        about: "move a habit on the board",
+    },
+    CommandInfo {
+        names: &["sort"],
+        usage: "sort name|remaining|streak",
+        about: "sort the board",
+    },
+    CommandInfo {
+        names: &["merge"],
+        usage: "merge <source> <target>",
+        about: "fold one habit into another",
+    },
+    CommandInfo {
+        names: &["find"],
+        usage: "find <name> <op> <value> | find clear",
+        about: "highlight days matching a comparison",
+    },
+    CommandInfo {
+        names: &["view"],
+        usage: "view day|week|month|year|recent [n]",
+        about: "switch the view of every habit",
+    },
+    CommandInfo {
+        names: &["mprev", "month-prev"],
+        usage: "mprev",
+        about: "show the month before",
+    },
+    CommandInfo {
+        names: &["mnext", "month-next"],
+        usage: "mnext",
+        about: "show the month after",
+    },
+    CommandInfo {
+        names: &["insights"],
+        usage: "insights <name>",
+        about: "best and worst weekday of a habit",
+    },
+    CommandInfo {
+        names: &["info"],
+        usage: "info <name>",
+        about: "dates, streaks and total of a habit",
+    },
+    CommandInfo {
+        names: &["list"],
+        usage: "list [--json]",
+        about: "show every habit, as json for scripts",
+    },
+    CommandInfo {
+        names: &["report"],
+        usage: "report streaks [path]",
+        about: "show or write a table of streaks",
+    },
+    CommandInfo {
+        names: &["export"],
+        usage: "export json|matrix <path> | export csv|ics <name> <path>",
+        about: "write habits out to a file",
+    },
+    CommandInfo {
+        names: &["import"],
+        usage: "import csv <name> <path> | import loop <path>",
+        about: "read entries from a file",
+    },
+    CommandInfo {
+        names: &["replay"],
+        usage: "replay <path>",
+        about: "apply the changes of a journal file",
+    },
+    CommandInfo {
+        names: &["repair"],
+        usage: "repair",
+        about: "prune empty entries",
+    },
+    CommandInfo {
+        names: &["profile"],
+        usage: "profile [name|default]",
+        about: "switch to another set of habits",
+    },
+    CommandInfo {
+        names: &["readonly"],
+        usage: "readonly on|off",
+        about: "refuse any change to habits",
+    },
+    CommandInfo {
+        names: &["undo", "u"],
+        usage: "undo",
+        about: "take back the last change",
+    },
+    CommandInfo {
+        names: &["redo"],
+        usage: "redo",
+        about: "apply the last undone change again",
+    },
+    CommandInfo {
+        names: &["help"],
+        usage: "help",
+        about: "list commands and keys",
+    },
+    CommandInfo {
+        names: &["quit", "q"],
+        usage: "quit",
+        about: "save and quit",
+    },
+];
+
+// commands and keys as listed by `:help` and `?`
+pub fn help_text() -> String {
+    let mut text = String::from("Commands\n\n");
+    for c in COMMANDS {
+        text.push_str(&format!(":{}\n    {}\n", c.usage, c.about));
+        if c.names.len() > 1 {
+            text.push_str(&format!("    also :{}\n", c.names[1..].join(", :")));
+        }
+    }
+    text.push_str("\nKeys\n\n");
+    let keys = ACTIONS
+        .iter()
+        .map(|a| (keymap::key_names(a.name), a.about))
+        .collect::<Vec<_>>();
+    let width = keys
+        .iter()
+        .map(|(k, _)| k.chars().count())
+        .max()
+        .unwrap_or(0);
+    for (keys, about) in keys {
+        text.push_str(&format!("{:w$}  {}\n", keys, about, w = width));
+    }
+    text
+}
+
+pub fn command_info(name: &str) -> Option<&'static CommandInfo> {
+    COMMANDS.iter().find(|c| c.names.contains(&name))
+}
+
+// a known command that `name` is likely a typo of, short
+// aliases are left out as nearly anything is close to them
+fn suggest(name: &str) -> Option<&'static str> {
+    let limit = if name.chars().count() < 4 { 1 } else { 2 };
+    COMMANDS
+        .iter()
+        .flat_map(|c| c.names.iter())
+        .filter(|n| n.len() > 2)
+        .map(|n| (edit_distance(name, n), *n))
+        .filter(|&(d, _)| d <= limit)
+        .min_by_key(|&(d, _)| d)
+        .map(|(_, n)| n)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for j in 0..b.len() {
+            let cost = if ca == b[j] { prev } else { prev + 1 };
+            prev = row[j + 1];
+            row[j + 1] = cost.min(row[j] + 1).min(prev + 1);
+        }
+    }
+    row[b.len()]
+}
+
 #[derive(Debug)]
 pub enum CommandLineError {
     InvalidCommand(String),
     InvalidArg(u32), // position
     NotEnoughArgs(String, u32),
+    // either of the above for a command with a known usage,
+    // along with the argument at fault if any
+    Usage(Option<u32>, &'static str),
 }
 
 impl std::error::Error for CommandLineError {}
@@ -75,30 +602,146 @@ impl std::error::Error for CommandLineError {}
 impl fmt::Display for CommandLineError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            CommandLineError::InvalidCommand(s) => write!(f, "Invalid command: `{}`", s),
+            CommandLineError::InvalidCommand(s) => match suggest(s) {
+                Some(n) => write!(f, "Unknown command: `{}`, did you mean `{}`?", s, n),
+                None => write!(f, "Unknown command: `{}`", s),
+            },
             CommandLineError::InvalidArg(p) => write!(f, "Invalid argument at position {}", p),
             CommandLineError::NotEnoughArgs(s, n) => {
                 write!(f, "Command `{}` requires atleast {} argument(s)!", s, n)
             }
+            CommandLineError::Usage(None, u) => write!(f, "Usage: :{}", u),
+            CommandLineError::Usage(Some(p), u) => {
+                write!(f, "Invalid argument at position {}, usage: :{}", p, u)
+            }
         }
     }
 }
 
 type Result<T> = std::result::Result<T, CommandLineError>;
 
+// longest range of days that `:fill` will touch at once
+const MAX_FILL_DAYS: i64 = 366;
+
 impl Command {
+    // whether the command changes habits, these are refused
+    // in read-only mode
+    pub fn is_edit(&self) -> bool {
+        !matches!(
+            self,
+            Command::MonthPrev
+                | Command::MonthNext
+                | Command::ExportCsv(..)
+                | Command::ExportIcs(..)
+                | Command::ExportMatrix(_)
+                | Command::ExportJson(_)
+                | Command::Profile(_)
+                | Command::DryRun(_)
+                | Command::Insights(_)
+                | Command::Info(_)
+                | Command::List(_)
+                | Command::ReportStreaks(_)
+                | Command::Filter(_)
+                | Command::Find(..)
+                | Command::FindClear
+                | Command::View(_)
+                | Command::ReadOnly(_)
+                | Command::Help
+                | Command::Quit
+                | Command::Blank
+        )
+    }
+
+    // parses a line typed into the command bar, errors about
+    // the arguments of a known command come with its usage
     pub fn from_string<P: AsRef<str>>(input: P) -> Result<Command> {
-        let mut strings: Vec<&str> = input.as_ref().trim().split(' ').collect();
+        let input = input.as_ref().trim();
+        let first = input.split(' ').next().unwrap_or_default();
+        Command::parse(input).map_err(|e| match (e, command_info(first)) {
+            (CommandLineError::InvalidArg(p), Some(info)) => {
+                CommandLineError::Usage(Some(p), info.usage)
+            }
+            (CommandLineError::NotEnoughArgs(..), Some(info)) => {
+                CommandLineError::Usage(None, info.usage)
+            }
+            (e, _) => e,
+        })
+    }
+
+    fn parse(input: &str) -> Result<Command> {
+        let mut strings: Vec<&str> = input.split(' ').collect();
         if strings.is_empty() {
             return Ok(Command::Blank);
         }
 
+        if strings.len() > 1 && strings.last() == Some(&"--dry-run") {
+            let flag = strings.len() - 1;
+            let command = Command::parse(&strings[..flag].join(" "))?;
+            return match command {
+                Command::Fill(..)
+                | Command::Merge(..)
+                | Command::Delete(_)
+                | Command::ForceDelete(_)
+                | Command::Repair => Ok(Command::DryRun(Box::new(command))),
+                _ => Err(CommandLineError::InvalidArg(flag as u32)),
+            };
+        }
+
         let first = strings.first().unwrap().to_string();
         let mut args: Vec<String> = strings.iter_mut().skip(1).map(|s| s.to_string()).collect();
         let mut _add = |auto: bool, first: String| {
             if args.is_empty() {
                 return Err(CommandLineError::NotEnoughArgs(first, 1));
             }
+            if let Some(g) = args.get(1).filter(|x| x.contains('.')) {
+                let goal = g
+                    .parse::<f64>()
+                    .map_err(|_| CommandLineError::InvalidArg(2))?;
+                return Ok(Command::AddFloat(args[0].to_string(), goal, auto));
+            }
+            if let Some(g) = args.get(1).filter(|x| x.contains('/')) {
+                let mut parts = g.splitn(2, '/');
+                let goal = parts
+                    .next()
+                    .and_then(|n| n.parse::<u32>().ok())
+                    .ok_or(CommandLineError::InvalidArg(2))?;
+                let period = match parts.next() {
+                    Some("day") | Some("d") => GoalPeriod::Daily,
+                    Some("week") | Some("w") => GoalPeriod::Weekly,
+                    Some("month") | Some("m") => GoalPeriod::Monthly,
+                    _ => return Err(CommandLineError::InvalidArg(2)),
+                };
+                return Ok(Command::AddPeriodic(
+                    args[0].to_string(),
+                    goal,
+                    period,
+                    auto,
+                ));
+            }
+            if let Some(g) = args.get(1).filter(|x| x.starts_with("<=")) {
+                let cap = g[2..]
+                    .parse::<u32>()
+                    .map_err(|_| CommandLineError::InvalidArg(2))?;
+                return Ok(Command::AddCapped(args[0].to_string(), cap, auto));
+            }
+            if let Some(g) = args
+                .get(1)
+                .filter(|x| x.starts_with('[') && x.ends_with(']'))
+            {
+                let tasks = g[1..g.len() - 1]
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|n| (1..=MAX_TASKS).contains(n))
+                    .ok_or(CommandLineError::InvalidArg(2))?;
+                return Ok(Command::AddChecklist(args[0].to_string(), tasks, auto));
+            }
+            if let Some(g) = args.get(1).filter(|x| x.ends_with('m')) {
+                let minutes = g
+                    .trim_end_matches('m')
+                    .parse::<u32>()
+                    .map_err(|_| CommandLineError::InvalidArg(2))?;
+                return Ok(Command::AddDuration(args[0].to_string(), minutes, auto));
+            }
             let goal = args
                 .get(1)
                 .map(|x| {
@@ -113,15 +756,38 @@ impl Command {
             ));
         };
 
+        if !first.is_empty() && command_info(&first).is_none() {
+            return Err(CommandLineError::InvalidCommand(first));
+        }
         match first.as_ref() {
             "add" | "a" => _add(false, first),
             "add-auto" | "aa" => _add(true, first),
-            "delete" | "d" => {
-                if args.is_empty() {
-                    return Err(CommandLineError::NotEnoughArgs(first, 1));
+            // names may hold spaces, the goal is always the last
+            // argument
+            "new" => match args.first().map(|k| k.as_str()) {
+                Some("count") => {
+                    if args.len() < 3 {
+                        return Err(CommandLineError::NotEnoughArgs(first, 3));
+                    }
+                    let last = args.len() - 1;
+                    let goal = args[last]
+                        .parse::<u32>()
+                        .map_err(|_| CommandLineError::InvalidArg(last as u32 + 1))?;
+                    Ok(Command::NewCount(args[1..last].join(" "), goal))
                 }
-                return Ok(Command::Delete(args[0].to_string()));
-            }
+                Some("bit") => {
+                    if args.len() < 2 {
+                        return Err(CommandLineError::NotEnoughArgs(first, 2));
+                    }
+                    Ok(Command::NewBit(args[1..].join(" ")))
+                }
+                Some(_) => Err(CommandLineError::InvalidArg(1)),
+                None => Err(CommandLineError::NotEnoughArgs(first, 2)),
+            },
+            // commands that take a habit act on the selection
+            // when it is left out
+            "delete" | "d" => Ok(Command::Delete(args.first().cloned())),
+            "delete!" | "d!" => Ok(Command::ForceDelete(args.first().cloned())),
             "track-up" | "tup" => {
                 if args.is_empty() {
                     return Err(CommandLineError::NotEnoughArgs(first, 1));
@@ -134,11 +800,446 @@ impl Command {
                 }
                 return Ok(Command::TrackDown(args[0].to_string()));
             }
+            "export" => {
+                if args.len() < 2 {
+                    return Err(CommandLineError::NotEnoughArgs(first, 2));
+                }
+                match args[0].as_ref() {
+                    "json" => Ok(Command::ExportJson(args[1].clone())),
+                    "matrix" => Ok(Command::ExportMatrix(args[1].clone())),
+                    "csv" | "ics" if args.len() < 3 => {
+                        Err(CommandLineError::NotEnoughArgs(first, 3))
+                    }
+                    "csv" => Ok(Command::ExportCsv(args[1].clone(), args[2].clone())),
+                    "ics" => Ok(Command::ExportIcs(args[1].clone(), args[2].clone())),
+                    _ => Err(CommandLineError::InvalidArg(1)),
+                }
+            }
+            "import" => {
+                if args.len() < 2 {
+                    return Err(CommandLineError::NotEnoughArgs(first, 2));
+                }
+                match args[0].as_ref() {
+                    "loop" => Ok(Command::ImportLoop(args[1..].join(" "))),
+                    "csv" if args.len() < 3 => Err(CommandLineError::NotEnoughArgs(first, 3)),
+                    "csv" => Ok(Command::ImportCsv(args[1].clone(), args[2].clone())),
+                    _ => Err(CommandLineError::InvalidArg(1)),
+                }
+            }
+            "color" => {
+                if args.is_empty() {
+                    return Err(CommandLineError::NotEnoughArgs(first, 1));
+                }
+                let color = if args.len() > 1 {
+                    Some(args[1..].join(" "))
+                } else {
+                    None
+                };
+                Ok(Command::Color(args[0].to_string(), color))
+            }
+            "archive" => Ok(Command::Archive(args.first().cloned())),
+            "unarchive" => Ok(Command::Unarchive(args.first().cloned())),
+            "rename" => {
+                if args.len() < 2 {
+                    return Err(CommandLineError::NotEnoughArgs(first, 2));
+                }
+                Ok(Command::Rename(args[0].to_string(), args[1..].join(" ")))
+            }
+            "convert" => {
+                if args.len() < 2 {
+                    return Err(CommandLineError::NotEnoughArgs(first, 2));
+                }
+                match args[1].as_ref() {
+                    "bit" => Ok(Command::Convert(args[0].to_string(), None)),
+                    "count" if args.len() < 3 => Err(CommandLineError::NotEnoughArgs(first, 3)),
+                    "count" => {
+                        let goal = args[2]
+                            .parse::<u32>()
+                            .map_err(|_| CommandLineError::InvalidArg(3))?;
+                        Ok(Command::Convert(args[0].to_string(), Some(goal)))
+                    }
+                    _ => Err(CommandLineError::InvalidArg(2)),
+                }
+            }
+            "clone" => {
+                if args.len() < 2 {
+                    return Err(CommandLineError::NotEnoughArgs(first, 2));
+                }
+                Ok(Command::Clone(args[0].to_string(), args[1..].join(" ")))
+            }
+            "goal" => {
+                if args.len() < 2 {
+                    return Err(CommandLineError::NotEnoughArgs(first, 2));
+                }
+                let goal = args[1]
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|&g| g > 0)
+                    .ok_or(CommandLineError::InvalidArg(2))?;
+                Ok(Command::Goal(args[0].to_string(), goal))
+            }
+            "tag" | "untag" => {
+                let (name, tag) = match args.len() {
+                    0 => return Err(CommandLineError::NotEnoughArgs(first, 1)),
+                    1 => (None, args[0].to_string()),
+                    _ => (Some(args[0].to_string()), args[1].to_string()),
+                };
+                if first == "tag" {
+                    return Ok(Command::Tag(name, tag));
+                }
+                Ok(Command::Untag(name, tag))
+            }
+            "find" => {
+                if args.first().map(|a| a.as_str()) == Some("clear") {
+                    return Ok(Command::FindClear);
+                }
+                if args.len() < 3 {
+                    return Err(CommandLineError::NotEnoughArgs(first, 3));
+                }
+                let op = Comparison::from_str(&args[1]).ok_or(CommandLineError::InvalidArg(2))?;
+                let value = match args[2].as_ref() {
+                    "true" => 1.0,
+                    "false" => 0.0,
+                    v => v
+                        .parse::<f64>()
+                        .map_err(|_| CommandLineError::InvalidArg(3))?,
+                };
+                Ok(Command::Find(args[0].to_string(), op, value))
+            }
+            "weekends" => {
+                if args.len() < 2 {
+                    return Err(CommandLineError::NotEnoughArgs(first, 2));
+                }
+                match args[1].as_ref() {
+                    "show" => Ok(Command::Weekends(args[0].to_string(), true)),
+                    "hide" => Ok(Command::Weekends(args[0].to_string(), false)),
+                    _ => Err(CommandLineError::InvalidArg(2)),
+                }
+            }
+            "glyph" => {
+                if args.is_empty() {
+                    return Err(CommandLineError::NotEnoughArgs(first, 1));
+                }
+                let mut glyphs = vec![];
+                for (arg, pos) in args[1..].iter().take(2).zip(2..) {
+                    let mut chars = arg.chars();
+                    match (chars.next(), chars.next()) {
+                        (Some(c), None) => glyphs.push(c),
+                        _ => return Err(CommandLineError::InvalidArg(pos)),
+                    }
+                }
+                Ok(Command::Glyphs(
+                    args[0].to_string(),
+                    glyphs.first().cloned(),
+                    glyphs.get(1).cloned(),
+                ))
+            }
+            "pause" => {
+                if args.len() < 2 {
+                    return Err(CommandLineError::NotEnoughArgs(first, 2));
+                }
+                let until = NaiveDate::parse_from_str(&args[1], "%Y-%m-%d")
+                    .map_err(|_| CommandLineError::InvalidArg(2))?;
+                Ok(Command::Pause(args[0].to_string(), until))
+            }
+            "resume" => {
+                if args.is_empty() {
+                    return Err(CommandLineError::NotEnoughArgs(first, 1));
+                }
+                Ok(Command::Resume(args[0].to_string()))
+            }
+            "set" => {
+                if args.len() < 3 {
+                    return Err(CommandLineError::NotEnoughArgs(first, 3));
+                }
+                let date = NaiveDate::parse_from_str(&args[1], "%Y-%m-%d")
+                    .map_err(|_| CommandLineError::InvalidArg(2))?;
+                Ok(Command::Set(args[0].to_string(), date, args[2].to_string()))
+            }
+            "fill" => {
+                if args.len() < 4 {
+                    return Err(CommandLineError::NotEnoughArgs(first, 4));
+                }
+                let from = NaiveDate::parse_from_str(&args[1], "%Y-%m-%d")
+                    .map_err(|_| CommandLineError::InvalidArg(2))?;
+                let to = NaiveDate::parse_from_str(&args[2], "%Y-%m-%d")
+                    .map_err(|_| CommandLineError::InvalidArg(3))?;
+                if from > to || (to - from).num_days() >= MAX_FILL_DAYS {
+                    return Err(CommandLineError::InvalidArg(3));
+                }
+                Ok(Command::Fill(
+                    args[0].to_string(),
+                    from,
+                    to,
+                    args[3].to_string(),
+                ))
+            }
+            "schedule" => {
+                if args.len() < 2 {
+                    return Err(CommandLineError::NotEnoughArgs(first, 2));
+                }
+                let schedule = match args[1].as_ref() {
+                    "everyday" | "daily" => Schedule::EveryDay,
+                    "weekdays" => Schedule::Weekdays(vec![
+                        Weekday::Mon,
+                        Weekday::Tue,
+                        Weekday::Wed,
+                        Weekday::Thu,
+                        Weekday::Fri,
+                    ]),
+                    "weekends" => Schedule::Weekdays(vec![Weekday::Sat, Weekday::Sun]),
+                    _ => {
+                        let mut days = vec![];
+                        for (day, pos) in args[1..].iter().zip(2..) {
+                            let day = day
+                                .parse::<Weekday>()
+                                .map_err(|_| CommandLineError::InvalidArg(pos))?;
+                            days.push(day);
+                        }
+                        Schedule::Weekdays(days)
+                    }
+                };
+                Ok(Command::Schedule(args[0].to_string(), schedule))
+            }
+            "filter" => {
+                if args.is_empty() {
+                    return Err(CommandLineError::NotEnoughArgs(first, 1));
+                }
+                match args[0].as_ref() {
+                    "clear" => Ok(Command::Filter(None)),
+                    tag => Ok(Command::Filter(Some(tag.to_string()))),
+                }
+            }
+            "view" => {
+                if args.is_empty() {
+                    return Err(CommandLineError::NotEnoughArgs(first, 1));
+                }
+                let mode = match args[0].as_ref() {
+                    "day" => ViewMode::Day,
+                    "week" => ViewMode::Week,
+                    "month" => ViewMode::Month,
+                    "year" => ViewMode::Year,
+                    "recent" => match args.get(1).map(|n| n.parse::<u32>()) {
+                        None => ViewMode::Recent(RECENT_DAYS),
+                        Some(Ok(n)) if (1..=MAX_RECENT_DAYS).contains(&n) => ViewMode::Recent(n),
+                        Some(_) => return Err(CommandLineError::InvalidArg(2)),
+                    },
+                    _ => return Err(CommandLineError::InvalidArg(1)),
+                };
+                Ok(Command::View(mode))
+            }
+            "move" => {
+                if args.len() < 2 {
+                    return Err(CommandLineError::NotEnoughArgs(first, 2));
+                }
+                match args[1].as_ref() {
+                    "up" => Ok(Command::MoveUp(args[0].to_string())),
+                    "down" => Ok(Command::MoveDown(args[0].to_string())),
+                    _ => Err(CommandLineError::InvalidArg(2)),
+                }
+            }
+            "merge" => {
+                if args.len() < 2 {
+                    return Err(CommandLineError::NotEnoughArgs(first, 2));
+                }
+                Ok(Command::Merge(args[0].to_string(), args[1].to_string()))
+            }
+            "negative" => {
+                if args.len() < 2 {
+                    return Err(CommandLineError::NotEnoughArgs(first, 2));
+                }
+                match args[1].as_ref() {
+                    "on" => Ok(Command::Negative(args[0].to_string(), true)),
+                    "off" => Ok(Command::Negative(args[0].to_string(), false)),
+                    _ => Err(CommandLineError::InvalidArg(2)),
+                }
+            }
+            "step" => {
+                if args.len() < 2 {
+                    return Err(CommandLineError::NotEnoughArgs(first, 2));
+                }
+                match args[1].parse::<u32>() {
+                    Ok(n) if n > 0 => Ok(Command::Step(args[0].to_string(), n)),
+                    _ => Err(CommandLineError::InvalidArg(2)),
+                }
+            }
+            "target" => {
+                if args.len() < 2 {
+                    return Err(CommandLineError::NotEnoughArgs(first, 2));
+                }
+                if args[1] == "off" {
+                    return Ok(Command::Target(args[0].to_string(), None));
+                }
+                if args.len() < 3 {
+                    return Err(CommandLineError::NotEnoughArgs(first, 3));
+                }
+                let total = args[1]
+                    .parse::<u32>()
+                    .map_err(|_| CommandLineError::InvalidArg(2))?;
+                let date = NaiveDate::parse_from_str(&args[2], "%Y-%m-%d")
+                    .map_err(|_| CommandLineError::InvalidArg(3))?;
+                Ok(Command::Target(args[0].to_string(), Some((total, date))))
+            }
+            "rolling" => {
+                if args.len() < 2 {
+                    return Err(CommandLineError::NotEnoughArgs(first, 2));
+                }
+                if args[1] == "off" {
+                    return Ok(Command::Rolling(args[0].to_string(), None));
+                }
+                let mut parts = args[1].splitn(2, '/').map(|p| p.parse::<u32>().ok());
+                match (parts.next().flatten(), parts.next().flatten()) {
+                    (Some(n), Some(m)) if n >= 1 && n <= m && m <= 366 => {
+                        Ok(Command::Rolling(args[0].to_string(), Some((n, m))))
+                    }
+                    _ => Err(CommandLineError::InvalidArg(2)),
+                }
+            }
+            "repair" => Ok(Command::Repair),
+            "profile" => match args.first().map(|p| p.as_str()) {
+                None | Some("default") => Ok(Command::Profile(None)),
+                Some(p) => Ok(Command::Profile(Some(p.to_string()))),
+            },
+            "sort" => {
+                if args.is_empty() {
+                    return Err(CommandLineError::NotEnoughArgs(first, 1));
+                }
+                let key = SortKey::parse(&args[0]).ok_or(CommandLineError::InvalidArg(1))?;
+                Ok(Command::Sort(key))
+            }
+            "replay" => {
+                if args.is_empty() {
+                    return Err(CommandLineError::NotEnoughArgs(first, 1));
+                }
+                Ok(Command::Replay(args.join(" ")))
+            }
+            "report" => {
+                if args.is_empty() {
+                    return Err(CommandLineError::NotEnoughArgs(first, 1));
+                }
+                match args[0].as_ref() {
+                    "streaks" => Ok(Command::ReportStreaks(args.get(1).cloned())),
+                    _ => Err(CommandLineError::InvalidArg(1)),
+                }
+            }
+            "insights" => {
+                if args.is_empty() {
+                    return Err(CommandLineError::NotEnoughArgs(first, 1));
+                }
+                Ok(Command::Insights(args[0].to_string()))
+            }
+            "info" => {
+                if args.is_empty() {
+                    return Err(CommandLineError::NotEnoughArgs(first, 1));
+                }
+                Ok(Command::Info(args[0].to_string()))
+            }
+            "list" => match args.first().map(|a| a.as_str()) {
+                None => Ok(Command::List(false)),
+                Some("--json") => Ok(Command::List(true)),
+                Some(_) => Err(CommandLineError::InvalidArg(1)),
+            },
+            "undo" | "u" => Ok(Command::Undo),
+            "redo" => Ok(Command::Redo),
             "mprev" | "month-prev" => return Ok(Command::MonthPrev),
             "mnext" | "month-next" => return Ok(Command::MonthNext),
+            "readonly" => match args.first().map(|a| a.as_str()) {
+                Some("on") => Ok(Command::ReadOnly(true)),
+                Some("off") => Ok(Command::ReadOnly(false)),
+                Some(_) => Err(CommandLineError::InvalidArg(1)),
+                None => Err(CommandLineError::NotEnoughArgs(first, 1)),
+            },
+            "help" => Ok(Command::Help),
             "q" | "quit" => return Ok(Command::Quit),
             "" => return Ok(Command::Blank),
             s => return Err(CommandLineError::InvalidCommand(s.into())),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_habits_by_name_prefix_and_substring() {
+        let names: Vec<String> = vec!["meditation".into(), "medicine".into(), "walk".into()];
+        assert_eq!(resolve_habit("walk", &names), Resolution::Exact("walk"));
+        assert_eq!(
+            resolve_habit("medit", &names),
+            Resolution::Exact("meditation")
+        );
+        assert_eq!(resolve_habit("ALK", &names), Resolution::Exact("walk"));
+        assert_eq!(
+            resolve_habit("medi", &names),
+            Resolution::Ambiguous(vec!["meditation", "medicine"])
+        );
+        assert_eq!(resolve_habit("run", &names), Resolution::NotFound);
+    }
+
+    #[test]
+    fn exact_name_wins_over_a_longer_one() {
+        let names: Vec<String> = vec!["read more".into(), "read".into()];
+        assert_eq!(resolve_habit("read", &names), Resolution::Exact("read"));
+    }
+
+    #[test]
+    fn fill_ranges_must_run_forward_and_stay_short() {
+        assert!(matches!(
+            Command::from_string("fill water 2026-03-02 2026-03-06 1"),
+            Ok(Command::Fill(..))
+        ));
+        assert!(Command::from_string("fill water 2026-03-06 2026-03-02 1").is_err());
+        assert!(Command::from_string("fill water 2025-01-01 2026-03-02 1").is_err());
+        assert!(Command::from_string("fill water 2026-03-02 03-06 1").is_err());
+    }
+
+    #[test]
+    fn new_takes_the_last_argument_as_the_goal() {
+        assert_eq!(
+            Command::from_string("new count drink water 8").unwrap(),
+            Command::NewCount("drink water".into(), 8)
+        );
+        assert_eq!(
+            Command::from_string("new bit go for a walk").unwrap(),
+            Command::NewBit("go for a walk".into())
+        );
+        assert!(Command::from_string("new count water").is_err());
+        assert!(Command::from_string("new count water lots").is_err());
+        assert!(Command::from_string("new bit").is_err());
+        assert!(Command::from_string("new float water 2").is_err());
+    }
+
+    #[test]
+    fn known_commands_parse_and_typos_are_named() {
+        assert_eq!(
+            Command::from_string("add walk 3").unwrap(),
+            Command::Add("walk".into(), Some(3), false)
+        );
+        assert_eq!(
+            Command::from_string("d walk").unwrap(),
+            Command::Delete(Some("walk".into()))
+        );
+        assert_eq!(
+            Command::from_string("goal walk 4").unwrap(),
+            Command::Goal("walk".into(), 4)
+        );
+        assert_eq!(Command::from_string("q").unwrap(), Command::Quit);
+
+        let typo = Command::from_string("gaol walk 4").unwrap_err();
+        assert_eq!(
+            typo.to_string(),
+            "Unknown command: `gaol`, did you mean `goal`?"
+        );
+        assert_eq!(
+            Command::from_string("goal walk lots")
+                .unwrap_err()
+                .to_string(),
+            "Invalid argument at position 2, usage: :goal <name> <goal>"
+        );
+        assert_eq!(
+            Command::from_string("goal walk").unwrap_err().to_string(),
+            "Usage: :goal <name> <goal>"
+        );
+    }
+}
@@ -0,0 +1,293 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::habit::HabitWrapper;
+use crate::save;
+
+pub fn to_csv(habit: &dyn HabitWrapper) -> String {
+    let mut dates = habit.tracked_dates();
+    dates.sort();
+
+    let mut csv = String::from("date,value\n");
+    for d in dates {
+        if let Some(v) = habit.raw_entry(d) {
+            csv.push_str(&format!("{},{}\n", d.format("%Y-%m-%d"), v));
+        }
+    }
+    csv
+}
+
+// one row per date that any habit tracked and one column per
+// habit, days a habit did not track are left empty
+pub fn to_matrix(habits: &[Box<dyn HabitWrapper>]) -> String {
+    let mut dates = habits
+        .iter()
+        .flat_map(|h| h.tracked_dates())
+        .collect::<Vec<_>>();
+    dates.sort();
+    dates.dedup();
+
+    let mut csv = String::from("date");
+    for h in habits {
+        csv.push(',');
+        csv.push_str(&csv_field(&h.name()));
+    }
+    csv.push('\n');
+    for d in dates {
+        csv.push_str(&d.format("%Y-%m-%d").to_string());
+        for h in habits {
+            csv.push(',');
+            if let Some(v) = h.raw_entry(d) {
+                csv.push_str(&csv_field(&v));
+            }
+        }
+        csv.push('\n');
+    }
+    csv
+}
+
+// quoted when it would otherwise break the row apart
+fn csv_field(s: &str) -> String {
+    if s.contains(&[',', '"', '\n'][..]) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_owned()
+    }
+}
+
+// an all-day event for every day `habit` reached its goal. the
+// uid spells out the name in hex, which keeps it unique across
+// habits whatever characters the name holds
+pub fn to_ics(habit: &dyn HabitWrapper) -> String {
+    let mut dates = habit.tracked_dates();
+    dates.retain(|&d| habit.reached_goal(d));
+    dates.sort();
+
+    let name = habit.name();
+    let id = name
+        .bytes()
+        .map(|b| format!("{:02x}", b))
+        .collect::<String>();
+    let summary = name
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,");
+    let mut ics = String::from("BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//dijo//EN\r\n");
+    for d in dates {
+        let day = d.format("%Y%m%d");
+        ics.push_str("BEGIN:VEVENT\r\n");
+        ics.push_str(&format!("UID:{}-{}@dijo\r\n", day, id));
+        ics.push_str(&format!("DTSTAMP:{}T000000Z\r\n", day));
+        ics.push_str(&format!("DTSTART;VALUE=DATE:{}\r\n", day));
+        ics.push_str(&format!("SUMMARY:{}\r\n", summary));
+        ics.push_str("END:VEVENT\r\n");
+    }
+    ics.push_str("END:VCALENDAR\r\n");
+    ics
+}
+
+// the whole tracker in the same format as the save files,
+// so an export can be dropped in as a habit file
+pub fn to_json(habits: &[Box<dyn HabitWrapper>]) -> serde_json::Result<String> {
+    save::to_json(habits)
+}
+
+#[derive(Serialize)]
+struct ListEntry {
+    name: String,
+    #[serde(rename = "type")]
+    kind: &'static str,
+    goal: u32,
+    remaining_today: u32,
+    streak: u32,
+}
+
+// habit names, one per line
+pub fn to_list(habits: &[&dyn HabitWrapper]) -> String {
+    habits.iter().map(|h| h.name() + "\n").collect()
+}
+
+// the same habits for scripts, names are escaped by serde
+pub fn to_list_json(habits: &[&dyn HabitWrapper], today: NaiveDate) -> serde_json::Result<String> {
+    let entries = habits
+        .iter()
+        .map(|h| ListEntry {
+            name: h.name(),
+            kind: h.kind(),
+            goal: h.goal(),
+            remaining_today: h.remaining(today),
+            streak: h.get_streak(today),
+        })
+        .collect::<Vec<_>>();
+    serde_json::to_string_pretty(&entries)
+}
+
+// current and longest streak of each habit as a plain text
+// table, shared by the streak report window and its file dump
+pub fn streak_table(habits: &[&dyn HabitWrapper], today: NaiveDate) -> String {
+    let mut rows = habits
+        .iter()
+        .map(|h| (h.name(), h.get_streak(today), h.longest_streak()))
+        .collect::<Vec<_>>();
+    rows.sort_by(|a, b| b.1.cmp(&a.1).then(b.2.cmp(&a.2)).then(a.0.cmp(&b.0)));
+
+    let mut table = format!("{:20} {:>7} {:>7}\n", "habit", "current", "longest");
+    for (name, current, longest) in rows {
+        table.push_str(&format!("{:20.20} {:>7} {:>7}\n", name, current, longest));
+    }
+    table
+}
+
+pub fn write_to<P: AsRef<Path>>(path: P, contents: &str) -> io::Result<()> {
+    fs::write(path, contents)
+}
+
+#[derive(Debug)]
+pub enum ImportError {
+    MissingField(usize), // line number
+    InvalidDate(usize),
+    InvalidValue(usize),
+}
+
+impl std::error::Error for ImportError {}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ImportError::MissingField(l) => write!(f, "Missing field on line {}", l),
+            ImportError::InvalidDate(l) => write!(f, "Invalid date on line {}", l),
+            ImportError::InvalidValue(l) => write!(f, "Invalid value on line {}", l),
+        }
+    }
+}
+
+// reads `date,value` rows into `habit`, overwriting existing entries,
+// nothing is inserted unless every row is valid
+pub fn import_csv(habit: &mut dyn HabitWrapper, data: &str) -> Result<usize, ImportError> {
+    let mut rows = vec![];
+    for (idx, line) in data.lines().enumerate() {
+        let line_nr = idx + 1;
+        let line = line.trim();
+        if line.is_empty() || (line_nr == 1 && line.to_lowercase().starts_with("date")) {
+            continue;
+        }
+        let mut fields = line.splitn(2, ',');
+        let (date, value) = match (fields.next(), fields.next()) {
+            (Some(d), Some(v)) => (d.trim(), v.trim()),
+            _ => return Err(ImportError::MissingField(line_nr)),
+        };
+        let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|_| ImportError::InvalidDate(line_nr))?;
+        if !habit.accepts_raw(value) {
+            return Err(ImportError::InvalidValue(line_nr));
+        }
+        rows.push((date, value));
+    }
+
+    for (date, value) in rows.iter() {
+        habit.insert_raw(*date, value);
+    }
+    Ok(rows.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::habit::{Bit, Count, Habit};
+    use chrono::NaiveDate;
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd(2026, 4, d)
+    }
+
+    #[test]
+    fn csv_of_a_small_habit() {
+        let mut h = Count::new("water", 3, false);
+        h.insert_entry(day(10), 2);
+        h.insert_entry(day(2), 5);
+        assert_eq!(
+            to_csv(&h).as_bytes(),
+            b"date,value\n2026-04-02,5\n2026-04-10,2\n"
+        );
+    }
+
+    #[test]
+    fn csv_of_an_empty_habit_is_the_header() {
+        assert_eq!(to_csv(&Count::new("water", 3, false)), "date,value\n");
+    }
+
+    #[test]
+    fn one_event_per_completed_day() {
+        let mut h = Count::new("water, cold", 3, false);
+        h.insert_entry(day(1), 3);
+        h.insert_entry(day(2), 1);
+        h.insert_entry(day(5), 4);
+        h.insert_entry(day(7), 3);
+        let ics = to_ics(&h);
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 3);
+        assert!(ics.contains("DTSTART;VALUE=DATE:20260405\r\n"));
+        assert!(!ics.contains("DTSTART;VALUE=DATE:20260402\r\n"));
+        assert!(ics.contains("SUMMARY:water\\, cold\r\n"));
+        let uids = ics
+            .lines()
+            .filter(|l| l.starts_with("UID:"))
+            .collect::<std::collections::HashSet<_>>();
+        assert_eq!(uids.len(), 3);
+    }
+
+    #[test]
+    fn matrix_lines_up_partly_overlapping_habits() {
+        let mut water = Count::new("water", 3, false);
+        water.insert_entry(day(1), 2);
+        water.insert_entry(day(3), 4);
+        let mut walk = Bit::new("walk, outside", false);
+        walk.insert_entry(day(3), true.into());
+        walk.insert_entry(day(5), false.into());
+        let habits: Vec<Box<dyn HabitWrapper>> = vec![Box::new(water), Box::new(walk)];
+        assert_eq!(
+            to_matrix(&habits),
+            "date,water,\"walk, outside\"\n\
+             2026-04-01,2,\n\
+             2026-04-03,4,1\n\
+             2026-04-05,,0\n"
+        );
+    }
+
+    #[test]
+    fn list_json_of_two_habits() {
+        let mut water = Count::new("water \"cold\"", 3, false);
+        water.insert_entry(day(9), 3);
+        water.insert_entry(day(10), 1);
+        let mut walk = Bit::new("walk", false);
+        walk.insert_entry(day(10), true.into());
+        let habits: [&dyn HabitWrapper; 2] = [&water, &walk];
+
+        let json = to_list_json(&habits, day(10)).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!([
+                {
+                    "name": "water \"cold\"",
+                    "type": "Count",
+                    "goal": 3,
+                    "remaining_today": 2,
+                    "streak": 0
+                },
+                {
+                    "name": "walk",
+                    "type": "Bit",
+                    "goal": 1,
+                    "remaining_today": 0,
+                    "streak": 1
+                }
+            ])
+        );
+        assert_eq!(to_list(&habits), "water \"cold\"\nwalk\n");
+    }
+}
@@ -4,8 +4,8 @@ use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 
 use crate::habit::prelude::default_auto;
-use crate::habit::traits::Habit;
-use crate::habit::{TrackEvent, ViewMode};
+use crate::habit::traits::{Habit, RawEntry};
+use crate::habit::{HabitMeta, TrackEvent, ViewMode};
 use crate::CONFIGURATION;
 
 #[derive(Copy, Clone, Debug, Serialize, Deserialize)]
@@ -14,15 +14,28 @@ pub struct CustomBool(bool);
 use std::fmt;
 impl fmt::Display for CustomBool {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{:^3}",
-            if self.0 {
-                CONFIGURATION.true_chr
-            } else {
-                CONFIGURATION.false_chr
-            }
-        )
+        let c = if self.0 {
+            CONFIGURATION.true_chr
+        } else {
+            CONFIGURATION.false_chr
+        };
+        f.pad(&c.to_string())
+    }
+}
+
+impl RawEntry for CustomBool {
+    fn to_raw(&self) -> String {
+        (self.0 as u8).to_string()
+    }
+    fn from_raw(raw: &str) -> Option<Self> {
+        match raw.trim().to_lowercase().as_ref() {
+            "1" | "true" => Some(CustomBool(true)),
+            "0" | "false" => Some(CustomBool(false)),
+            _ => None,
+        }
+    }
+    fn merge(&self, other: &Self) -> Self {
+        CustomBool(self.0 || other.0)
     }
 }
 
@@ -38,13 +51,21 @@ pub struct Bit {
     stats: HashMap<NaiveDate, CustomBool>,
     goal: CustomBool,
 
+    // done on a day if done on at least `n` of the `m` days
+    // ending on it, rather than on the day itself
+    #[serde(default)]
+    rolling: Option<(u32, u32)>,
+
     #[serde(default = "default_auto")]
     auto: bool,
 
-    #[serde(skip)]
+    #[serde(flatten)]
+    meta: HabitMeta,
+
+    #[serde(default)]
     view_month_offset: u32,
 
-    #[serde(skip)]
+    #[serde(default)]
     view_mode: ViewMode,
 }
 
@@ -54,13 +75,26 @@ impl Bit {
             name: name.as_ref().to_owned(),
             stats: HashMap::new(),
             goal: CustomBool(true),
+            rolling: None,
             auto,
+            meta: HabitMeta::default(),
             view_month_offset: 0,
             view_mode: ViewMode::Day,
         };
     }
 }
 
+impl Bit {
+    // days marked done among the `m` days ending on `date`,
+    // days before the first entry count as not done
+    fn done_in_window(&self, date: NaiveDate, m: u32) -> u32 {
+        (0..m as i64)
+            .map(|i| date - chrono::Duration::days(i))
+            .filter(|d| matches!(self.stats.get(d), Some(v) if v.0))
+            .count() as u32
+    }
+}
+
 impl Habit for Bit {
     type HabitType = CustomBool;
     fn name(&self) -> String {
@@ -69,16 +103,41 @@ impl Habit for Bit {
     fn set_name(&mut self, n: impl AsRef<str>) {
         self.name = n.as_ref().to_owned();
     }
+    fn clone_config(&self, name: &str) -> Self {
+        Bit {
+            name: name.to_owned(),
+            stats: HashMap::new(),
+            goal: self.goal,
+            rolling: self.rolling,
+            auto: self.auto,
+            meta: self.meta.config(),
+            view_month_offset: self.view_month_offset,
+            view_mode: self.view_mode,
+        }
+    }
     fn set_goal(&mut self, g: Self::HabitType) {
         self.goal = g;
     }
+    fn set_goal_raw(&mut self, _: &str) -> bool {
+        // a bit is either done or not, there is nothing to adjust
+        false
+    }
     fn get_by_date(&self, date: NaiveDate) -> Option<&Self::HabitType> {
         self.stats.get(&date)
     }
+    fn tracked_dates(&self) -> Vec<NaiveDate> {
+        self.stats.keys().cloned().collect()
+    }
     fn insert_entry(&mut self, date: NaiveDate, val: Self::HabitType) {
         *self.stats.entry(date).or_insert(val) = val;
     }
+    fn clear_entry(&mut self, date: NaiveDate) {
+        self.stats.remove(&date);
+    }
     fn reached_goal(&self, date: NaiveDate) -> bool {
+        if let Some((n, m)) = self.rolling {
+            return self.done_in_window(date, m) >= n;
+        }
         if let Some(val) = self.stats.get(&date) {
             if val.0 >= self.goal.0 {
                 return true;
@@ -87,6 +146,9 @@ impl Habit for Bit {
         return false;
     }
     fn remaining(&self, date: NaiveDate) -> u32 {
+        if self.rolling.is_some() {
+            return !self.reached_goal(date) as u32;
+        }
         if let Some(val) = self.stats.get(&date) {
             if val.0 {
                 return 0;
@@ -100,6 +162,15 @@ impl Habit for Bit {
     fn goal(&self) -> u32 {
         return 1;
     }
+    // shades days by how close their window came to the goal
+    fn progress(&self, date: NaiveDate) -> Option<f64> {
+        let (n, m) = self.rolling?;
+        Some(self.done_in_window(date, m) as f64 / n as f64)
+    }
+    fn set_rolling(&mut self, rolling: Option<(u32, u32)>) -> bool {
+        self.rolling = rolling;
+        true
+    }
     fn modify(&mut self, date: NaiveDate, event: TrackEvent) {
         if let Some(val) = self.stats.get_mut(&date) {
             match event {
@@ -133,4 +204,53 @@ impl Habit for Bit {
     fn is_auto(&self) -> bool {
         self.auto
     }
+    fn meta(&self) -> &HabitMeta {
+        &self.meta
+    }
+    fn meta_mut(&mut self) -> &mut HabitMeta {
+        &mut self.meta
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd(2026, 1, d)
+    }
+
+    // three of the last five days
+    fn rolling(done: &[u32]) -> Bit {
+        let mut b = Bit::new("gym", false);
+        b.set_rolling(Some((3, 5)));
+        for &d in done {
+            b.insert_entry(day(d), true.into());
+        }
+        b
+    }
+
+    #[test]
+    fn rolling_goal_with_exactly_n_days() {
+        let b = rolling(&[10, 12, 14]);
+        assert!(b.reached_goal(day(14)));
+        assert!(!b.reached_goal(day(12)));
+        assert_eq!(b.remaining(day(14)), 0);
+    }
+
+    #[test]
+    fn rolling_goal_below_n_days() {
+        let b = rolling(&[9, 12, 14]);
+        assert!(!b.reached_goal(day(14)));
+        assert!(!b.reached_goal(day(13)));
+    }
+
+    #[test]
+    fn rolling_window_before_the_first_entry() {
+        let b = rolling(&[1, 2]);
+        assert!(!b.reached_goal(day(1)));
+        assert!(!b.reached_goal(day(2)));
+        let b = rolling(&[1, 2, 3]);
+        assert!(b.reached_goal(day(3)));
+    }
 }
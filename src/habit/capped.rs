@@ -0,0 +1,218 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::habit::prelude::default_auto;
+use crate::habit::traits::Habit;
+use crate::habit::{HabitMeta, TrackEvent, ViewMode};
+use crate::utils;
+
+// a count that should stay at or below its goal, such as
+// "no more than 2 coffees", untracked days are within the cap
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Capped {
+    name: String,
+    stats: HashMap<NaiveDate, u32>,
+    goal: u32,
+
+    #[serde(default = "default_auto")]
+    auto: bool,
+
+    #[serde(flatten)]
+    meta: HabitMeta,
+
+    #[serde(default)]
+    view_month_offset: u32,
+
+    #[serde(default)]
+    view_mode: ViewMode,
+}
+
+impl Capped {
+    pub fn new(name: impl AsRef<str>, goal: u32, auto: bool) -> Self {
+        Capped {
+            name: name.as_ref().to_owned(),
+            stats: HashMap::new(),
+            goal,
+            auto,
+            meta: HabitMeta::default(),
+            view_month_offset: 0,
+            view_mode: ViewMode::Day,
+        }
+    }
+
+    // days before the first entry are not part of the habit,
+    // without this bound streaks would never end
+    fn first_tracked(&self) -> Option<NaiveDate> {
+        self.stats.keys().min().cloned()
+    }
+}
+
+impl Habit for Capped {
+    type HabitType = u32;
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+    fn set_name(&mut self, n: impl AsRef<str>) {
+        self.name = n.as_ref().to_owned();
+    }
+    fn clone_config(&self, name: &str) -> Self {
+        Capped {
+            name: name.to_owned(),
+            stats: HashMap::new(),
+            goal: self.goal,
+            auto: self.auto,
+            meta: self.meta.config(),
+            view_month_offset: self.view_month_offset,
+            view_mode: self.view_mode,
+        }
+    }
+    fn set_goal(&mut self, g: Self::HabitType) {
+        self.goal = g;
+    }
+    // staying under the cap is all there is to it
+    fn reaching_raw(&self) -> Option<String> {
+        None
+    }
+    fn get_by_date(&self, date: NaiveDate) -> Option<&Self::HabitType> {
+        self.stats.get(&date)
+    }
+    fn tracked_dates(&self) -> Vec<NaiveDate> {
+        self.stats.keys().cloned().collect()
+    }
+    fn insert_entry(&mut self, date: NaiveDate, val: Self::HabitType) {
+        *self.stats.entry(date).or_insert(val) = val;
+    }
+    fn clear_entry(&mut self, date: NaiveDate) {
+        self.stats.remove(&date);
+    }
+    fn reached_goal(&self, date: NaiveDate) -> bool {
+        self.amount(date) <= self.goal
+    }
+    fn remaining(&self, date: NaiveDate) -> u32 {
+        self.goal.saturating_sub(self.amount(date))
+    }
+    fn goal(&self) -> u32 {
+        self.goal
+    }
+    fn over_limit(&self, date: NaiveDate) -> bool {
+        !self.reached_goal(date)
+    }
+    fn amount(&self, date: NaiveDate) -> u32 {
+        self.stats.get(&date).cloned().unwrap_or(0)
+    }
+    fn get_streak(&self, date: NaiveDate) -> u32 {
+        let first = match self.first_tracked() {
+            Some(d) => d,
+            None => return 0,
+        };
+        let mut streak = 0;
+        let mut day = date;
+        while day >= first {
+            if self.is_resting(day) {
+                // rest days neither extend nor break a streak
+            } else if self.reached_goal(day) {
+                streak += 1;
+            } else if self.is_scheduled(day) {
+                break;
+            }
+            match day.pred_opt() {
+                Some(d) => day = d,
+                None => break,
+            }
+        }
+        streak
+    }
+    fn longest_streak(&self) -> u32 {
+        let first = match self.first_tracked() {
+            Some(d) => d,
+            None => return 0,
+        };
+        let today = utils::today();
+        let last = self.stats.keys().max().cloned().unwrap_or(first).max(today);
+        let (mut longest, mut current) = (0, 0);
+        for i in 0..=(last - first).num_days() {
+            let day = first + chrono::Duration::days(i);
+            if self.is_resting(day) {
+                continue;
+            } else if self.reached_goal(day) {
+                current += 1;
+                longest = longest.max(current);
+            } else if self.is_scheduled(day) {
+                current = 0;
+            }
+        }
+        longest
+    }
+    fn modify(&mut self, date: NaiveDate, event: TrackEvent) {
+        if let Some(val) = self.stats.get_mut(&date) {
+            match event {
+                TrackEvent::Increment => *val = val.saturating_add(1),
+                TrackEvent::Decrement => {
+                    if *val > 0 {
+                        *val -= 1
+                    } else {
+                        self.stats.remove(&date);
+                    };
+                }
+            }
+        } else if event == TrackEvent::Increment {
+            self.insert_entry(date, 1);
+        }
+    }
+    fn set_view_month_offset(&mut self, offset: u32) {
+        self.view_month_offset = offset;
+    }
+    fn view_month_offset(&self) -> u32 {
+        self.view_month_offset
+    }
+    fn set_view_mode(&mut self, mode: ViewMode) {
+        self.view_mode = mode;
+    }
+    fn view_mode(&self) -> ViewMode {
+        self.view_mode
+    }
+    fn is_auto(&self) -> bool {
+        self.auto
+    }
+    fn meta(&self) -> &HabitMeta {
+        &self.meta
+    }
+    fn meta_mut(&mut self) -> &mut HabitMeta {
+        &mut self.meta
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn days_under_at_and_over_the_cap() {
+        let d = |n| NaiveDate::from_ymd(2026, 1, n);
+        let mut c = Capped::new("coffee", 3, false);
+        c.insert_entry(d(1), 1);
+        c.insert_entry(d(2), 3);
+        c.insert_entry(d(3), 4);
+
+        assert!(c.reached_goal(d(1)) && !c.over_limit(d(1)));
+        assert_eq!(c.remaining(d(1)), 2);
+        assert!(c.reached_goal(d(2)) && !c.over_limit(d(2)));
+        assert_eq!(c.remaining(d(2)), 0);
+        assert!(!c.reached_goal(d(3)) && c.over_limit(d(3)));
+        assert_eq!(c.remaining(d(3)), 0);
+        assert_eq!(c.get_streak(d(2)), 2);
+        assert_eq!(c.get_streak(d(3)), 0);
+    }
+
+    #[test]
+    fn increment_saturates_at_the_largest_value() {
+        let d = NaiveDate::from_ymd(2026, 1, 5);
+        let mut c = Capped::new("x", 3, false);
+        c.insert_entry(d, u32::MAX);
+        c.modify(d, TrackEvent::Increment);
+        assert_eq!(c.get_by_date(d), Some(&u32::MAX));
+    }
+}
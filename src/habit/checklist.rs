@@ -0,0 +1,260 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::habit::prelude::default_auto;
+use crate::habit::traits::{Habit, RawEntry};
+use crate::habit::{HabitMeta, TrackEvent, ViewMode};
+
+// a byte has room for no more sub-tasks than this
+pub const MAX_TASKS: u32 = 8;
+
+// sub-tasks ticked off on a day, one bit each, alongside
+// the number of sub-tasks the habit had at the time
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Checks {
+    mask: u8,
+    tasks: u8,
+}
+
+impl Checks {
+    fn required(&self) -> u8 {
+        ((1u16 << self.tasks) - 1) as u8
+    }
+    fn done(&self) -> u32 {
+        (self.mask & self.required()).count_ones()
+    }
+}
+
+impl fmt::Display for Checks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(&format!("{}/{}", self.done(), self.tasks))
+    }
+}
+
+impl RawEntry for Checks {
+    fn to_raw(&self) -> String {
+        self.mask.to_string()
+    }
+    // the number of sub-tasks is filled in by the habit
+    fn from_raw(raw: &str) -> Option<Self> {
+        raw.trim()
+            .parse::<u8>()
+            .ok()
+            .map(|mask| Checks { mask, tasks: 0 })
+    }
+    fn merge(&self, other: &Self) -> Self {
+        Checks {
+            mask: self.mask | other.mask,
+            tasks: self.tasks.max(other.tasks),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Checklist {
+    name: String,
+    stats: HashMap<NaiveDate, Checks>,
+    tasks: u8,
+
+    #[serde(default = "default_auto")]
+    auto: bool,
+
+    #[serde(flatten)]
+    meta: HabitMeta,
+
+    #[serde(default)]
+    view_month_offset: u32,
+
+    #[serde(default)]
+    view_mode: ViewMode,
+}
+
+impl Checklist {
+    pub fn new(name: impl AsRef<str>, tasks: u32, auto: bool) -> Self {
+        Checklist {
+            name: name.as_ref().to_owned(),
+            stats: HashMap::new(),
+            tasks: tasks.clamp(1, MAX_TASKS) as u8,
+            auto,
+            meta: HabitMeta::default(),
+            view_month_offset: 0,
+            view_mode: ViewMode::Day,
+        }
+    }
+
+    fn checks(&self, date: NaiveDate) -> Checks {
+        self.stats.get(&date).cloned().unwrap_or(Checks {
+            mask: 0,
+            tasks: self.tasks,
+        })
+    }
+}
+
+impl Habit for Checklist {
+    type HabitType = Checks;
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+    fn set_name(&mut self, n: impl AsRef<str>) {
+        self.name = n.as_ref().to_owned();
+    }
+    fn clone_config(&self, name: &str) -> Self {
+        Checklist {
+            name: name.to_owned(),
+            stats: HashMap::new(),
+            tasks: self.tasks,
+            auto: self.auto,
+            meta: self.meta.config(),
+            view_month_offset: self.view_month_offset,
+            view_mode: self.view_mode,
+        }
+    }
+    fn set_goal(&mut self, g: Self::HabitType) {
+        self.tasks = g.tasks;
+    }
+    // days already tracked keep the sub-tasks they had
+    fn set_goal_raw(&mut self, raw: &str) -> bool {
+        match raw.trim().parse::<u32>() {
+            Ok(n) if (1..=MAX_TASKS).contains(&n) => {
+                self.tasks = n as u8;
+                true
+            }
+            _ => false,
+        }
+    }
+    // every sub-task ticked off
+    fn reaching_raw(&self) -> Option<String> {
+        Some(((1u16 << self.tasks) - 1).to_string())
+    }
+    fn get_by_date(&self, date: NaiveDate) -> Option<&Self::HabitType> {
+        self.stats.get(&date)
+    }
+    fn tracked_dates(&self) -> Vec<NaiveDate> {
+        self.stats.keys().cloned().collect()
+    }
+    fn insert_entry(&mut self, date: NaiveDate, val: Self::HabitType) {
+        let val = Checks {
+            tasks: self.tasks,
+            ..val
+        };
+        *self.stats.entry(date).or_insert(val) = val;
+    }
+    fn clear_entry(&mut self, date: NaiveDate) {
+        self.stats.remove(&date);
+    }
+    fn reached_goal(&self, date: NaiveDate) -> bool {
+        let c = self.checks(date);
+        c.mask & c.required() == c.required()
+    }
+    // measured against the sub-tasks the day had
+    fn remaining(&self, date: NaiveDate) -> u32 {
+        let c = self.checks(date);
+        c.tasks as u32 - c.done()
+    }
+    fn goal(&self) -> u32 {
+        self.tasks as u32
+    }
+    fn amount(&self, date: NaiveDate) -> u32 {
+        self.checks(date).done()
+    }
+    fn progress(&self, date: NaiveDate) -> Option<f64> {
+        let c = self.checks(date);
+        Some(c.done() as f64 / c.tasks as f64)
+    }
+    // ticks off the first open sub-task, or unticks the last
+    // one that was done
+    fn modify(&mut self, date: NaiveDate, event: TrackEvent) {
+        let mut c = self.checks(date);
+        match event {
+            TrackEvent::Increment => {
+                let open = !c.mask & c.required();
+                if open == 0 {
+                    return;
+                }
+                c.mask |= open & open.wrapping_neg();
+            }
+            TrackEvent::Decrement => {
+                if c.mask == 0 {
+                    self.stats.remove(&date);
+                    return;
+                }
+                c.mask &= !(1 << (7 - c.mask.leading_zeros()));
+            }
+        }
+        self.insert_entry(date, c);
+    }
+    fn set_view_month_offset(&mut self, offset: u32) {
+        self.view_month_offset = offset;
+    }
+    fn view_month_offset(&self) -> u32 {
+        self.view_month_offset
+    }
+    fn set_view_mode(&mut self, mode: ViewMode) {
+        self.view_mode = mode;
+    }
+    fn view_mode(&self) -> ViewMode {
+        self.view_mode
+    }
+    fn is_auto(&self) -> bool {
+        self.auto
+    }
+    fn meta(&self) -> &HabitMeta {
+        &self.meta
+    }
+    fn meta_mut(&mut self) -> &mut HabitMeta {
+        &mut self.meta
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd(2026, 2, d)
+    }
+
+    #[test]
+    fn toggling_ticks_off_tasks_in_order() {
+        let mut c = Checklist::new("chores", 3, false);
+        c.modify(day(1), TrackEvent::Increment);
+        c.modify(day(1), TrackEvent::Increment);
+        assert_eq!(c.get_by_date(day(1)).unwrap().mask, 0b011);
+        assert_eq!(c.amount(day(1)), 2);
+        assert_eq!(c.remaining(day(1)), 1);
+        c.modify(day(1), TrackEvent::Decrement);
+        assert_eq!(c.get_by_date(day(1)).unwrap().mask, 0b001);
+        assert_eq!(c.get_by_date(day(1)).unwrap().to_string(), "1/3");
+    }
+
+    #[test]
+    fn goal_is_reached_once_every_task_is_done() {
+        let mut c = Checklist::new("chores", 3, false);
+        for _ in 0..2 {
+            c.modify(day(1), TrackEvent::Increment);
+        }
+        assert!(!c.reached_goal(day(1)));
+        c.modify(day(1), TrackEvent::Increment);
+        assert!(c.reached_goal(day(1)));
+        assert_eq!(c.remaining(day(1)), 0);
+        c.modify(day(1), TrackEvent::Increment);
+        assert_eq!(c.get_by_date(day(1)).unwrap().mask, 0b111);
+    }
+
+    #[test]
+    fn new_goal_leaves_past_days_alone() {
+        let mut c = Checklist::new("chores", 2, false);
+        c.modify(day(1), TrackEvent::Increment);
+        c.modify(day(1), TrackEvent::Increment);
+        assert!(c.set_goal_raw("3"));
+        assert_eq!(c.get_by_date(day(1)).unwrap().tasks, 2);
+        assert!(c.reached_goal(day(1)));
+        assert_eq!(c.remaining(day(1)), 0);
+        assert!(!c.reached_goal(day(2)));
+        assert_eq!(c.remaining(day(2)), 3);
+    }
+}
@@ -1,25 +1,85 @@
 use std::collections::HashMap;
 
-use chrono::NaiveDate;
-use serde::{Deserialize, Serialize};
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Deserializer, Serialize};
 
-use crate::habit::prelude::default_auto;
-use crate::habit::traits::Habit;
-use crate::habit::{TrackEvent, ViewMode};
+use crate::habit::prelude::{default_auto, default_step};
+use crate::habit::traits::{Habit, RawEntry};
+use crate::habit::{GoalPeriod, HabitMeta, TrackEvent, ViewMode};
+
+impl RawEntry for u32 {
+    fn to_raw(&self) -> String {
+        self.to_string()
+    }
+    fn from_raw(raw: &str) -> Option<Self> {
+        raw.trim().parse().ok()
+    }
+    fn merge(&self, other: &Self) -> Self {
+        self.saturating_add(*other)
+    }
+}
+
+impl RawEntry for i32 {
+    fn to_raw(&self) -> String {
+        self.to_string()
+    }
+    fn from_raw(raw: &str) -> Option<Self> {
+        raw.trim().parse().ok()
+    }
+    fn merge(&self, other: &Self) -> Self {
+        self.saturating_add(*other)
+    }
+}
+
+// entries are read wider than they are kept, counts saved back
+// when they could not go negative may lie past `i32::MAX`
+fn clamped_stats<'de, D>(deserializer: D) -> Result<HashMap<NaiveDate, i32>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let wide = HashMap::<NaiveDate, i64>::deserialize(deserializer)?;
+    Ok(wide
+        .into_iter()
+        .map(|(d, v)| (d, v.clamp(i32::MIN as i64, i32::MAX as i64) as i32))
+        .collect())
+}
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Count {
     name: String,
-    stats: HashMap<NaiveDate, u32>,
+    #[serde(deserialize_with = "clamped_stats")]
+    stats: HashMap<NaiveDate, i32>,
     goal: u32,
 
+    #[serde(default)]
+    goal_period: GoalPeriod,
+
+    // lets decrements go below zero, for habits that
+    // track a balance
+    #[serde(default)]
+    allow_negative: bool,
+
+    // amount a single increment or decrement changes by
+    #[serde(default = "default_step")]
+    step: u32,
+
+    // total to reach by `target_date`, counted from the day
+    // the habit was created
+    #[serde(default)]
+    target_total: Option<u32>,
+    #[serde(default)]
+    target_date: Option<NaiveDate>,
+
     #[serde(default = "default_auto")]
     auto: bool,
 
-    #[serde(skip)]
+    #[serde(flatten)]
+    meta: HabitMeta,
+
+    #[serde(default)]
     view_month_offset: u32,
 
-    #[serde(skip)]
+    #[serde(default)]
     view_mode: ViewMode,
 }
 
@@ -29,15 +89,42 @@ impl Count {
             name: name.as_ref().to_owned(),
             stats: HashMap::new(),
             goal,
+            goal_period: GoalPeriod::Daily,
+            allow_negative: false,
+            step: 1,
+            target_total: None,
+            target_date: None,
             auto,
+            meta: HabitMeta::default(),
             view_month_offset: 0,
             view_mode: ViewMode::Day,
         };
     }
 }
 
+impl Count {
+    pub fn set_goal_period(&mut self, period: GoalPeriod) {
+        self.goal_period = period;
+    }
+
+    // sum of entries in the goal period that `date` falls in,
+    // wide enough that no number of days can overflow it
+    fn period_total(&self, date: NaiveDate) -> i64 {
+        let in_period = |d: &NaiveDate| match self.goal_period {
+            GoalPeriod::Daily => *d == date,
+            GoalPeriod::Weekly => d.iso_week() == date.iso_week(),
+            GoalPeriod::Monthly => d.year() == date.year() && d.month() == date.month(),
+        };
+        self.stats
+            .iter()
+            .filter(|(d, _)| in_period(d))
+            .map(|(_, &v)| v as i64)
+            .sum()
+    }
+}
+
 impl Habit for Count {
-    type HabitType = u32;
+    type HabitType = i32;
 
     fn name(&self) -> String {
         return self.name.clone();
@@ -45,18 +132,46 @@ impl Habit for Count {
     fn set_name(&mut self, n: impl AsRef<str>) {
         self.name = n.as_ref().to_owned();
     }
+    fn clone_config(&self, name: &str) -> Self {
+        Count {
+            name: name.to_owned(),
+            stats: HashMap::new(),
+            goal: self.goal,
+            goal_period: self.goal_period,
+            allow_negative: self.allow_negative,
+            step: self.step,
+            target_total: self.target_total,
+            target_date: self.target_date,
+            auto: self.auto,
+            meta: self.meta.config(),
+            view_month_offset: self.view_month_offset,
+            view_mode: self.view_mode,
+        }
+    }
     fn set_goal(&mut self, g: Self::HabitType) {
-        self.goal = g;
+        self.goal = g.max(0) as u32;
     }
     fn get_by_date(&self, date: NaiveDate) -> Option<&Self::HabitType> {
         self.stats.get(&date)
     }
+    fn tracked_dates(&self) -> Vec<NaiveDate> {
+        self.stats.keys().cloned().collect()
+    }
     fn insert_entry(&mut self, date: NaiveDate, val: Self::HabitType) {
         *self.stats.entry(date).or_insert(val) = val;
     }
+    fn clear_entry(&mut self, date: NaiveDate) {
+        self.stats.remove(&date);
+    }
     fn reached_goal(&self, date: NaiveDate) -> bool {
-        if let Some(val) = self.stats.get(&date) {
-            if val >= &self.goal {
+        if self.is_log_only() {
+            return true;
+        }
+        if self.goal_period != GoalPeriod::Daily {
+            return self.period_total(date) >= self.goal as i64;
+        }
+        if let Some(&val) = self.stats.get(&date) {
+            if val as i64 >= self.goal as i64 {
                 return true;
             }
         }
@@ -65,24 +180,101 @@ impl Habit for Count {
     fn remaining(&self, date: NaiveDate) -> u32 {
         if self.reached_goal(date) {
             return 0;
-        } else {
-            if let Some(val) = self.stats.get(&date) {
-                return self.goal - val;
-            } else {
-                return self.goal;
-            }
         }
+        // negative totals leave more than the goal to do, though
+        // never more than fits
+        let left = self.goal as i64 - self.period_total(date);
+        left.max(0).min(u32::MAX as i64) as u32
     }
     fn goal(&self) -> u32 {
         return self.goal;
     }
+    // a goal of zero keeps a log without a target
+    fn is_log_only(&self) -> bool {
+        self.goal == 0
+    }
+    fn progress(&self, date: NaiveDate) -> Option<f64> {
+        if self.goal == 0 {
+            return None;
+        }
+        Some(self.period_total(date) as f64 / self.goal as f64)
+    }
+    fn fill(&self, date: NaiveDate) -> Option<f64> {
+        if self.goal == 0 {
+            return None;
+        }
+        self.stats.get(&date).map(|&v| v as f64 / self.goal as f64)
+    }
+    fn amount(&self, date: NaiveDate) -> u32 {
+        self.stats.get(&date).cloned().unwrap_or(0).max(0) as u32
+    }
+    fn accepts_raw(&self, raw: &str) -> bool {
+        match i32::from_raw(raw) {
+            Some(v) => v >= 0 || self.allow_negative,
+            None => false,
+        }
+    }
+    fn insert_raw(&mut self, date: NaiveDate, raw: &str) -> bool {
+        if !self.accepts_raw(raw) {
+            return false;
+        }
+        self.insert_entry(date, i32::from_raw(raw).unwrap());
+        true
+    }
+    fn set_allow_negative(&mut self, allow: bool) -> bool {
+        self.allow_negative = allow;
+        true
+    }
+    fn goal_period(&self) -> GoalPeriod {
+        self.goal_period
+    }
+    fn set_target(&mut self, target: Option<(u32, NaiveDate)>) -> bool {
+        self.target_total = target.map(|t| t.0);
+        self.target_date = target.map(|t| t.1);
+        true
+    }
+    // compares the share of the target reached so far with the
+    // share of days gone by, today is not over yet and so does
+    // not count as gone by
+    fn on_track(&self, today: NaiveDate) -> Option<bool> {
+        let (total, deadline) = match (self.target_total, self.target_date) {
+            (Some(t), Some(d)) => (t, d),
+            _ => return None,
+        };
+        if total == 0 {
+            return Some(true);
+        }
+        let start = self.created();
+        let span = (deadline - start).num_days();
+        let elapsed = if span <= 0 {
+            1.
+        } else {
+            ((today - start).num_days() as f64 / span as f64).clamp(0., 1.)
+        };
+        let done: i64 = self
+            .stats
+            .iter()
+            .filter(|(&d, _)| d >= start && d <= today.min(deadline))
+            .map(|(_, &v)| v.max(0) as i64)
+            .sum();
+        Some(done as f64 / total as f64 >= elapsed)
+    }
+    fn set_step(&mut self, step: u32) -> bool {
+        self.step = step;
+        true
+    }
+    // a decrement that would overshoot stops at zero unless
+    // the habit may go below it
     fn modify(&mut self, date: NaiveDate, event: TrackEvent) {
+        let step = self.step.min(i32::MAX as u32) as i32;
         if let Some(val) = self.stats.get_mut(&date) {
             match event {
-                TrackEvent::Increment => *val += 1,
+                TrackEvent::Increment => *val = val.saturating_add(step),
                 TrackEvent::Decrement => {
-                    if *val > 0 {
-                        *val -= 1
+                    if self.allow_negative {
+                        *val = val.saturating_sub(step)
+                    } else if *val > 0 {
+                        *val -= step.min(*val)
                     } else {
                         self.stats.remove(&date);
                     };
@@ -90,7 +282,8 @@ impl Habit for Count {
             }
         } else {
             match event {
-                TrackEvent::Increment => self.insert_entry(date, 1),
+                TrackEvent::Increment => self.insert_entry(date, step),
+                TrackEvent::Decrement if self.allow_negative => self.insert_entry(date, -step),
                 _ => {}
             };
         }
@@ -110,4 +303,164 @@ impl Habit for Count {
     fn is_auto(&self) -> bool {
         self.auto
     }
+    fn meta(&self) -> &HabitMeta {
+        &self.meta
+    }
+    fn meta_mut(&mut self) -> &mut HabitMeta {
+        &mut self.meta
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd(2026, 1, d)
+    }
+
+    #[test]
+    fn weekly_goal_across_a_month_boundary() {
+        let date = |m, d| NaiveDate::from_ymd(2026, m, d);
+        let mut c = Count::new("runs", 3, false);
+        c.set_goal_period(GoalPeriod::Weekly);
+        c.insert_entry(date(3, 30), 1);
+        assert_eq!(c.remaining(date(4, 1)), 2);
+        c.insert_entry(date(4, 2), 2);
+        assert!(c.reached_goal(date(3, 31)));
+        assert!(c.reached_goal(date(4, 5)));
+        assert_eq!(c.remaining(date(4, 5)), 0);
+        assert!(!c.reached_goal(date(4, 6)));
+        assert_eq!(c.remaining(date(4, 6)), 3);
+        assert_eq!(c.remaining(date(3, 29)), 3);
+    }
+
+    #[test]
+    fn decrement_past_zero() {
+        let mut c = Count::new("x", 3, false);
+        c.insert_entry(day(5), 1);
+        c.modify(day(5), TrackEvent::Decrement);
+        assert_eq!(c.get_by_date(day(5)), Some(&0));
+        c.modify(day(5), TrackEvent::Decrement);
+        assert_eq!(c.get_by_date(day(5)), None);
+        c.modify(day(5), TrackEvent::Decrement);
+        assert_eq!(c.get_by_date(day(5)), None);
+        assert!(!c.accepts_raw("-2"));
+
+        c.set_allow_negative(true);
+        c.modify(day(5), TrackEvent::Decrement);
+        c.modify(day(5), TrackEvent::Decrement);
+        assert_eq!(c.get_by_date(day(5)), Some(&-2));
+        assert_eq!(c.amount(day(5)), 0);
+        assert_eq!(c.remaining(day(5)), 5);
+        assert!(c.accepts_raw("-2"));
+    }
+
+    #[test]
+    fn old_unsigned_entries_still_load() {
+        let c: Count =
+            serde_json::from_str(r#"{"name":"x","stats":{"2026-01-05":4},"goal":3,"auto":false}"#)
+                .unwrap();
+        assert_eq!(c.get_by_date(day(5)), Some(&4));
+        assert!(!c.allow_negative);
+
+        let c: Count = serde_json::from_str(
+            r#"{"name":"x","stats":{"2026-01-05":3000000000,"2026-01-06":-5},"goal":3}"#,
+        )
+        .unwrap();
+        assert_eq!(c.get_by_date(day(5)), Some(&i32::MAX));
+        assert_eq!(c.get_by_date(day(6)), Some(&-5));
+    }
+
+    #[test]
+    fn step_applies_to_both_directions() {
+        let mut c = Count::new("x", 50, false);
+        c.set_step(10);
+        c.modify(day(5), TrackEvent::Increment);
+        assert_eq!(c.get_by_date(day(5)), Some(&10));
+
+        c.insert_entry(day(6), 5);
+        c.modify(day(6), TrackEvent::Decrement);
+        assert_eq!(c.get_by_date(day(6)), Some(&0));
+        c.modify(day(6), TrackEvent::Decrement);
+        assert_eq!(c.get_by_date(day(6)), None);
+    }
+
+    #[test]
+    fn target_pace_against_a_fixed_today() {
+        let mut c = Count::new("books", 0, false);
+        Habit::set_created(&mut c, day(1));
+        assert_eq!(Habit::on_track(&c, day(16)), None);
+        Habit::set_target(&mut c, Some((100, day(31))));
+        c.insert_entry(day(3), 40);
+        assert_eq!(Habit::on_track(&c, day(16)), Some(false));
+        c.insert_entry(day(10), 20);
+        assert_eq!(Habit::on_track(&c, day(16)), Some(true));
+    }
+
+    #[test]
+    fn goal_of_zero_only_logs() {
+        let mut c = Count::new("pages", 0, false);
+        assert!(c.reached_goal(day(5)));
+        assert_eq!(c.remaining(day(5)), 0);
+        c.insert_entry(day(5), 12);
+        assert!(c.reached_goal(day(5)));
+        assert_eq!(c.remaining(day(5)), 0);
+        assert!(c.is_log_only());
+    }
+
+    #[test]
+    fn hand_edited_largest_value_loads_and_increments() {
+        let j = r#"[{"type":"Count","name":"x","stats":{"2026-01-05":4294967295},"goal":3}]"#;
+        let mut habits = crate::save::from_json(j).unwrap();
+        let h = &mut habits[0];
+        h.modify(day(5), TrackEvent::Increment);
+        assert_eq!(h.raw_entry(day(5)), Some(i32::MAX.to_string()));
+        assert!(h.reached_goal(day(5)));
+        assert_eq!(h.remaining(day(5)), 0);
+        assert_eq!(h.total(), i32::MAX as u64);
+    }
+
+    #[test]
+    fn increment_saturates_at_the_largest_value() {
+        let mut c = Count::new("x", 3, false);
+        c.insert_entry(day(5), i32::MAX);
+        c.modify(day(5), TrackEvent::Increment);
+        assert_eq!(c.get_by_date(day(5)), Some(&i32::MAX));
+        assert!(c.reached_goal(day(5)));
+        assert_eq!(c.remaining(day(5)), 0);
+        assert_eq!(c.total(), i32::MAX as u64);
+    }
+
+    #[test]
+    fn value_over_the_goal_leaves_nothing_remaining() {
+        let mut c = Count::new("x", 3, false);
+        c.insert_entry(day(5), 10);
+        assert!(c.reached_goal(day(5)));
+        assert_eq!(c.remaining(day(5)), 0);
+        c.goal = u32::MAX;
+        assert!(!c.reached_goal(day(5)));
+        assert_eq!(c.remaining(day(5)), u32::MAX - 10);
+    }
+
+    #[test]
+    fn decrement_saturates_at_the_smallest_value() {
+        let mut c = Count::new("x", 3, false);
+        c.set_allow_negative(true);
+        c.insert_entry(day(5), i32::MIN);
+        c.modify(day(5), TrackEvent::Decrement);
+        assert_eq!(c.get_by_date(day(5)), Some(&i32::MIN));
+        assert_eq!(c.remaining(day(5)), 3 + 2u32.pow(31));
+    }
+
+    #[test]
+    fn month_and_year_sums_saturate() {
+        let mut c = Count::new("x", 3, false);
+        c.insert_entry(day(5), i32::MAX);
+        c.insert_entry(day(6), i32::MAX);
+        c.insert_entry(day(7), i32::MAX);
+        assert_eq!(c.sum_in_month(day(5)), u32::MAX);
+        assert_eq!(c.sum_in_year(day(5)), u32::MAX);
+        assert_eq!(c.total(), 3 * i32::MAX as u64);
+    }
 }
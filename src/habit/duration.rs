@@ -0,0 +1,193 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::habit::prelude::default_auto;
+use crate::habit::traits::{Habit, RawEntry};
+use crate::habit::{HabitMeta, TrackEvent, ViewMode};
+
+const STEP: u32 = 5;
+
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Minutes(u32);
+
+impl fmt::Display for Minutes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (h, m) = (self.0 / 60, self.0 % 60);
+        let width = f.width().unwrap_or(0);
+        let full = format!("{}:{:02}", h, m);
+
+        // h:mm rarely fits a grid cell, degrade to the most
+        // significant part instead of chopping digits off
+        if width == 0 || full.len() <= width {
+            f.pad(&full)
+        } else if h == 0 {
+            f.pad(&format!(":{:02}", m))
+        } else {
+            f.pad(&format!("{}h", h))
+        }
+    }
+}
+
+impl RawEntry for Minutes {
+    fn to_raw(&self) -> String {
+        self.0.to_string()
+    }
+    fn from_raw(raw: &str) -> Option<Self> {
+        raw.trim().parse().ok().map(Minutes)
+    }
+    fn merge(&self, other: &Self) -> Self {
+        Minutes(self.0.saturating_add(other.0))
+    }
+}
+
+impl From<u32> for Minutes {
+    fn from(m: u32) -> Self {
+        Minutes(m)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Duration {
+    name: String,
+    stats: HashMap<NaiveDate, Minutes>,
+    goal: u32,
+
+    #[serde(default = "default_auto")]
+    auto: bool,
+
+    #[serde(flatten)]
+    meta: HabitMeta,
+
+    #[serde(default)]
+    view_month_offset: u32,
+
+    #[serde(default)]
+    view_mode: ViewMode,
+}
+
+impl Duration {
+    pub fn new(name: impl AsRef<str>, goal: u32, auto: bool) -> Self {
+        Duration {
+            name: name.as_ref().to_owned(),
+            stats: HashMap::new(),
+            goal,
+            auto,
+            meta: HabitMeta::default(),
+            view_month_offset: 0,
+            view_mode: ViewMode::Day,
+        }
+    }
+}
+
+impl Habit for Duration {
+    type HabitType = Minutes;
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+    fn set_name(&mut self, n: impl AsRef<str>) {
+        self.name = n.as_ref().to_owned();
+    }
+    fn clone_config(&self, name: &str) -> Self {
+        Duration {
+            name: name.to_owned(),
+            stats: HashMap::new(),
+            goal: self.goal,
+            auto: self.auto,
+            meta: self.meta.config(),
+            view_month_offset: self.view_month_offset,
+            view_mode: self.view_mode,
+        }
+    }
+    fn set_goal(&mut self, g: Self::HabitType) {
+        self.goal = g.0;
+    }
+    fn get_by_date(&self, date: NaiveDate) -> Option<&Self::HabitType> {
+        self.stats.get(&date)
+    }
+    fn tracked_dates(&self) -> Vec<NaiveDate> {
+        self.stats.keys().cloned().collect()
+    }
+    fn insert_entry(&mut self, date: NaiveDate, val: Self::HabitType) {
+        *self.stats.entry(date).or_insert(val) = val;
+    }
+    fn clear_entry(&mut self, date: NaiveDate) {
+        self.stats.remove(&date);
+    }
+    fn reached_goal(&self, date: NaiveDate) -> bool {
+        if let Some(val) = self.stats.get(&date) {
+            if val.0 >= self.goal {
+                return true;
+            }
+        }
+        false
+    }
+    fn remaining(&self, date: NaiveDate) -> u32 {
+        if self.reached_goal(date) {
+            return 0;
+        }
+        let done = self.stats.get(&date).map(|v| v.0).unwrap_or(0);
+        self.goal - done
+    }
+    fn goal(&self) -> u32 {
+        self.goal
+    }
+    fn amount(&self, date: NaiveDate) -> u32 {
+        self.stats.get(&date).map_or(0, |v| v.0)
+    }
+    fn modify(&mut self, date: NaiveDate, event: TrackEvent) {
+        if let Some(val) = self.stats.get_mut(&date) {
+            match event {
+                TrackEvent::Increment => val.0 = val.0.saturating_add(STEP),
+                TrackEvent::Decrement => {
+                    if val.0 > 0 {
+                        val.0 = val.0.saturating_sub(STEP);
+                    } else {
+                        self.stats.remove(&date);
+                    }
+                }
+            }
+        } else if event == TrackEvent::Increment {
+            self.insert_entry(date, Minutes(STEP));
+        }
+    }
+    fn set_view_month_offset(&mut self, offset: u32) {
+        self.view_month_offset = offset;
+    }
+    fn view_month_offset(&self) -> u32 {
+        self.view_month_offset
+    }
+    fn set_view_mode(&mut self, mode: ViewMode) {
+        self.view_mode = mode;
+    }
+    fn view_mode(&self) -> ViewMode {
+        self.view_mode
+    }
+    fn is_auto(&self) -> bool {
+        self.auto
+    }
+    fn meta(&self) -> &HabitMeta {
+        &self.meta
+    }
+    fn meta_mut(&mut self) -> &mut HabitMeta {
+        &mut self.meta
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn increment_saturates_at_the_largest_value() {
+        let d = NaiveDate::from_ymd(2026, 1, 5);
+        let mut m = Duration::new("x", 30, false);
+        m.insert_entry(d, Minutes(u32::MAX - 1));
+        m.modify(d, TrackEvent::Increment);
+        assert_eq!(m.amount(d), u32::MAX);
+        assert_eq!(m.remaining(d), 0);
+    }
+}
@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::habit::prelude::default_auto;
+use crate::habit::traits::{Habit, RawEntry};
+use crate::habit::{HabitMeta, TrackEvent, ViewMode};
+
+const EPSILON: f64 = 1e-6;
+
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub struct FloatData(f64);
+
+impl fmt::Display for FloatData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = format!("{:.1}", self.0);
+        // drop the decimal when it does not fit the cell
+        match f.width() {
+            Some(width) if s.len() > width => f.pad(&format!("{:.0}", self.0)),
+            _ => f.pad(&s),
+        }
+    }
+}
+
+impl RawEntry for FloatData {
+    fn to_raw(&self) -> String {
+        self.0.to_string()
+    }
+    fn from_raw(raw: &str) -> Option<Self> {
+        raw.trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|v| v.is_finite() && *v >= 0.)
+            .map(FloatData)
+    }
+    fn merge(&self, other: &Self) -> Self {
+        FloatData(self.0 + other.0)
+    }
+}
+
+impl From<f64> for FloatData {
+    fn from(v: f64) -> Self {
+        FloatData(v)
+    }
+}
+
+fn default_step() -> f64 {
+    1.0
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FloatCount {
+    name: String,
+    stats: HashMap<NaiveDate, FloatData>,
+    goal: f64,
+
+    #[serde(default = "default_step")]
+    step: f64,
+
+    #[serde(default = "default_auto")]
+    auto: bool,
+
+    #[serde(flatten)]
+    meta: HabitMeta,
+
+    #[serde(default)]
+    view_month_offset: u32,
+
+    #[serde(default)]
+    view_mode: ViewMode,
+}
+
+impl FloatCount {
+    pub fn new(name: impl AsRef<str>, goal: f64, auto: bool) -> Self {
+        FloatCount {
+            name: name.as_ref().to_owned(),
+            stats: HashMap::new(),
+            goal,
+            step: default_step(),
+            auto,
+            meta: HabitMeta::default(),
+            view_month_offset: 0,
+            view_mode: ViewMode::Day,
+        }
+    }
+}
+
+impl Habit for FloatCount {
+    type HabitType = FloatData;
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+    fn set_name(&mut self, n: impl AsRef<str>) {
+        self.name = n.as_ref().to_owned();
+    }
+    fn clone_config(&self, name: &str) -> Self {
+        FloatCount {
+            name: name.to_owned(),
+            stats: HashMap::new(),
+            goal: self.goal,
+            step: self.step,
+            auto: self.auto,
+            meta: self.meta.config(),
+            view_month_offset: self.view_month_offset,
+            view_mode: self.view_mode,
+        }
+    }
+    fn set_goal(&mut self, g: Self::HabitType) {
+        self.goal = g.0;
+    }
+    fn get_by_date(&self, date: NaiveDate) -> Option<&Self::HabitType> {
+        self.stats.get(&date)
+    }
+    fn tracked_dates(&self) -> Vec<NaiveDate> {
+        self.stats.keys().cloned().collect()
+    }
+    fn insert_entry(&mut self, date: NaiveDate, val: Self::HabitType) {
+        *self.stats.entry(date).or_insert(val) = val;
+    }
+    fn clear_entry(&mut self, date: NaiveDate) {
+        self.stats.remove(&date);
+    }
+    fn reached_goal(&self, date: NaiveDate) -> bool {
+        if let Some(val) = self.stats.get(&date) {
+            if val.0 + EPSILON >= self.goal {
+                return true;
+            }
+        }
+        false
+    }
+    fn remaining(&self, date: NaiveDate) -> u32 {
+        if self.reached_goal(date) {
+            return 0;
+        }
+        let done = self.stats.get(&date).map(|v| v.0).unwrap_or(0.);
+        (self.goal - done).ceil() as u32
+    }
+    fn goal(&self) -> u32 {
+        self.goal.ceil() as u32
+    }
+    fn amount(&self, date: NaiveDate) -> u32 {
+        self.stats.get(&date).map_or(0, |v| v.0.round() as u32)
+    }
+    fn modify(&mut self, date: NaiveDate, event: TrackEvent) {
+        let step = self.step;
+        if let Some(val) = self.stats.get_mut(&date) {
+            match event {
+                TrackEvent::Increment => val.0 += step,
+                TrackEvent::Decrement => {
+                    if val.0 > EPSILON {
+                        val.0 = (val.0 - step).max(0.);
+                    } else {
+                        self.stats.remove(&date);
+                    }
+                }
+            }
+        } else if event == TrackEvent::Increment {
+            self.insert_entry(date, FloatData(step));
+        }
+    }
+    fn set_view_month_offset(&mut self, offset: u32) {
+        self.view_month_offset = offset;
+    }
+    fn view_month_offset(&self) -> u32 {
+        self.view_month_offset
+    }
+    fn set_view_mode(&mut self, mode: ViewMode) {
+        self.view_mode = mode;
+    }
+    fn view_mode(&self) -> ViewMode {
+        self.view_mode
+    }
+    fn is_auto(&self) -> bool {
+        self.auto
+    }
+    fn meta(&self) -> &HabitMeta {
+        &self.meta
+    }
+    fn meta_mut(&mut self) -> &mut HabitMeta {
+        &mut self.meta
+    }
+}
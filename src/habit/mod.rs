@@ -7,5 +7,19 @@ pub use count::Count;
 mod bit;
 pub use bit::Bit;
 
+mod float;
+pub use float::FloatCount;
+
+mod duration;
+pub use duration::Duration;
+
+mod capped;
+pub use capped::Capped;
+
+mod checklist;
+pub use checklist::{Checklist, MAX_TASKS};
+
 mod prelude;
-pub use prelude::{TrackEvent, ViewMode};
+pub use prelude::{
+    GoalPeriod, HabitMeta, Schedule, TrackEvent, ViewMode, MAX_RECENT_DAYS, RECENT_DAYS,
+};
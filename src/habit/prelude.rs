@@ -1,4 +1,6 @@
+use chrono::{Datelike, NaiveDate, Weekday};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::default;
 use std::fmt;
 
@@ -14,14 +16,35 @@ pub enum ViewMode {
     Week,
     Month,
     Year,
+    // a single row of the last few days, whatever month they
+    // fall in
+    Recent(u32),
 }
 
+// days shown by `Recent` when no number is given, and the
+// most it may show
+pub const RECENT_DAYS: u32 = 14;
+pub const MAX_RECENT_DAYS: u32 = 60;
+
 impl default::Default for ViewMode {
     fn default() -> Self {
         ViewMode::Day
     }
 }
 
+impl ViewMode {
+    // mode that follows this one when cycling, `Month` is
+    // drawn just like `Day` so it is left out
+    pub fn next(self) -> Self {
+        match self {
+            ViewMode::Day => ViewMode::Week,
+            ViewMode::Week => ViewMode::Year,
+            ViewMode::Year => ViewMode::Recent(RECENT_DAYS),
+            ViewMode::Month | ViewMode::Recent(_) => ViewMode::Day,
+        }
+    }
+}
+
 impl fmt::Display for ViewMode {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -29,6 +52,50 @@ impl fmt::Display for ViewMode {
             ViewMode::Week => write!(f, "WEEK"),
             ViewMode::Month => write!(f, "MONTH"),
             ViewMode::Year => write!(f, "YEAR"),
+            ViewMode::Recent(n) => write!(f, "LAST {}", n),
+        }
+    }
+}
+
+// span of days whose entries add up towards the goal
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum GoalPeriod {
+    #[default]
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+// days on which a habit is expected to be done, days off the
+// schedule neither break streaks nor count as misses
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum Schedule {
+    #[default]
+    EveryDay,
+    Weekdays(Vec<Weekday>),
+}
+
+impl Schedule {
+    pub fn includes(&self, date: NaiveDate) -> bool {
+        match self {
+            Schedule::EveryDay => true,
+            Schedule::Weekdays(days) => days.is_empty() || days.contains(&date.weekday()),
+        }
+    }
+}
+
+impl HabitMeta {
+    // settings worth carrying over to a copy of a habit, what
+    // happened on particular days is left behind
+    pub fn config(&self) -> HabitMeta {
+        HabitMeta {
+            color: self.color.clone(),
+            tags: self.tags.clone(),
+            schedule: self.schedule.clone(),
+            true_chr: self.true_chr,
+            false_chr: self.false_chr,
+            skip_weekends: self.skip_weekends,
+            ..HabitMeta::default()
         }
     }
 }
@@ -36,3 +103,47 @@ impl fmt::Display for ViewMode {
 pub fn default_auto() -> bool {
     false
 }
+
+pub fn default_step() -> u32 {
+    1
+}
+
+// user facing settings common to every kind of habit,
+// flattened into the habit when serialized
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HabitMeta {
+    pub color: Option<String>,
+    pub archived: bool,
+    pub notes: HashMap<NaiveDate, String>,
+    pub tags: Vec<String>,
+    pub schedule: Schedule,
+
+    // rest days, neither done nor missed
+    pub frozen: HashSet<NaiveDate>,
+
+    // drawn in place of entries that did or did not reach
+    // the goal, overriding the global characters
+    pub true_chr: Option<char>,
+    pub false_chr: Option<char>,
+
+    // day the habit was added, older save files lack it and
+    // fall back to their first entry
+    pub created: Option<NaiveDate>,
+
+    // leaves saturdays and sundays out of the day view
+    pub skip_weekends: bool,
+
+    // days from `paused_from` up to the day before `paused_until`
+    // are rest days, the habit stays on the board meanwhile
+    pub paused_from: Option<NaiveDate>,
+    pub paused_until: Option<NaiveDate>,
+
+    // day that keys act upon, today if unset
+    #[serde(skip)]
+    pub cursor: Option<NaiveDate>,
+
+    // days matched by the last `:find`, never saved
+    #[serde(skip)]
+    pub highlights: HashSet<NaiveDate>,
+}
@@ -1,21 +1,49 @@
-use chrono::NaiveDate;
+use std::collections::HashSet;
+
+use chrono::{Datelike, NaiveDate};
 use cursive::direction::Direction;
 use cursive::event::{Event, EventResult};
+use cursive::theme::Color;
 use cursive::{Printer, Vec2};
 
 use typetag;
 
-use crate::habit::{Bit, Count, TrackEvent, ViewMode};
+use crate::habit::{
+    Bit, Capped, Checklist, Count, Duration, FloatCount, GoalPeriod, HabitMeta, Schedule,
+    TrackEvent, ViewMode,
+};
+use crate::utils;
 use crate::views::ShadowView;
 
+// plain text representation of a single day's entry, used
+// when moving data in and out of dijo
+pub trait RawEntry {
+    fn to_raw(&self) -> String;
+    fn from_raw(raw: &str) -> Option<Self>
+    where
+        Self: Sized;
+    // both entries of a day folded into one, used when
+    // merging habits
+    fn merge(&self, other: &Self) -> Self
+    where
+        Self: Sized;
+}
+
 pub trait Habit {
-    type HabitType;
+    type HabitType: RawEntry;
 
     fn set_name(&mut self, name: impl AsRef<str>);
     fn set_goal(&mut self, goal: Self::HabitType);
     fn name(&self) -> String;
+    // a habit named `name` set up like this one, without
+    // any entries
+    fn clone_config(&self, name: &str) -> Self
+    where
+        Self: Sized;
     fn get_by_date(&self, date: NaiveDate) -> Option<&Self::HabitType>;
+    fn tracked_dates(&self) -> Vec<NaiveDate>;
     fn insert_entry(&mut self, date: NaiveDate, val: Self::HabitType);
+    fn clear_entry(&mut self, date: NaiveDate);
     fn reached_goal(&self, date: NaiveDate) -> bool;
     fn remaining(&self, date: NaiveDate) -> u32;
     fn goal(&self) -> u32;
@@ -28,18 +56,435 @@ pub trait Habit {
     fn view_mode(&self) -> ViewMode;
 
     fn is_auto(&self) -> bool;
+
+    fn meta(&self) -> &HabitMeta;
+    fn meta_mut(&mut self) -> &mut HabitMeta;
+
+    fn color(&self) -> Option<Color> {
+        self.meta()
+            .color
+            .as_ref()
+            .and_then(|c| utils::parse_color(c))
+    }
+    fn set_color(&mut self, color: Option<String>) {
+        self.meta_mut().color = color;
+    }
+    fn is_archived(&self) -> bool {
+        self.meta().archived
+    }
+    fn set_archived(&mut self, archived: bool) {
+        self.meta_mut().archived = archived;
+    }
+    fn get_note(&self, date: NaiveDate) -> Option<&String> {
+        self.meta().notes.get(&date)
+    }
+    fn set_note(&mut self, date: NaiveDate, text: &str) {
+        if text.trim().is_empty() {
+            self.meta_mut().notes.remove(&date);
+        } else {
+            self.meta_mut().notes.insert(date, text.trim().to_owned());
+        }
+    }
+    fn tags(&self) -> &[String] {
+        &self.meta().tags
+    }
+    // character that replaces the entry on `date`, if the
+    // habit overrides it
+    fn glyph(&self, date: NaiveDate) -> Option<char> {
+        self.get_by_date(date)?;
+        if self.reached_goal(date) {
+            self.meta().true_chr
+        } else {
+            self.meta().false_chr
+        }
+    }
+    fn set_glyphs(&mut self, true_chr: Option<char>, false_chr: Option<char>) {
+        self.meta_mut().true_chr = true_chr;
+        self.meta_mut().false_chr = false_chr;
+    }
+    fn skips_weekends(&self) -> bool {
+        self.meta().skip_weekends
+    }
+    fn set_skip_weekends(&mut self, skip: bool) {
+        self.meta_mut().skip_weekends = skip;
+    }
+    fn cursor(&self) -> NaiveDate {
+        self.meta().cursor.unwrap_or_else(utils::today)
+    }
+    fn set_cursor(&mut self, date: NaiveDate) {
+        self.meta_mut().cursor = Some(date);
+    }
+    fn is_highlighted(&self, date: NaiveDate) -> bool {
+        self.meta().highlights.contains(&date)
+    }
+    fn set_highlights(&mut self, dates: HashSet<NaiveDate>) {
+        self.meta_mut().highlights = dates;
+    }
+    fn is_frozen(&self, date: NaiveDate) -> bool {
+        self.meta().frozen.contains(&date)
+    }
+    fn freeze(&mut self, date: NaiveDate) {
+        self.meta_mut().frozen.insert(date);
+    }
+    fn unfreeze(&mut self, date: NaiveDate) {
+        self.meta_mut().frozen.remove(&date);
+    }
+    fn is_paused(&self, date: NaiveDate) -> bool {
+        match (self.meta().paused_from, self.meta().paused_until) {
+            (Some(from), Some(until)) => from <= date && date < until,
+            _ => false,
+        }
+    }
+    fn paused_until(&self) -> Option<NaiveDate> {
+        self.meta()
+            .paused_until
+            .filter(|_| self.meta().paused_from.is_some())
+    }
+    // pauses from `today` until the day before `until`, or ends
+    // the pause with none. days already spent paused are kept as
+    // rest days, so a later pause cannot turn them into misses
+    fn set_pause(&mut self, today: NaiveDate, until: Option<NaiveDate>) {
+        if let (Some(from), Some(end)) = (self.meta().paused_from, self.meta().paused_until) {
+            let mut day = from;
+            while day < end.min(today) {
+                self.freeze(day);
+                day = day.succ();
+            }
+        }
+        self.meta_mut().paused_from = until.map(|_| today);
+        self.meta_mut().paused_until = until;
+    }
+    // neither done nor missed, whether frozen or paused
+    fn is_resting(&self, date: NaiveDate) -> bool {
+        self.is_frozen(date) || self.is_paused(date)
+    }
+    fn toggle_freeze(&mut self, date: NaiveDate) {
+        if self.is_frozen(date) {
+            self.unfreeze(date);
+        } else {
+            self.freeze(date);
+        }
+    }
+    // first day the habit existed, entries made further back
+    // move it back with them
+    fn created(&self) -> NaiveDate {
+        let first = self.tracked_dates().into_iter().min();
+        match (self.meta().created, first) {
+            (Some(c), Some(f)) => c.min(f),
+            (Some(c), None) => c,
+            (None, Some(f)) => f,
+            (None, None) => utils::today(),
+        }
+    }
+    fn set_created(&mut self, date: NaiveDate) {
+        self.meta_mut().created = Some(date);
+    }
+    // days that streaks and completion rates take into account
+    fn counts_towards_goal(&self, date: NaiveDate) -> bool {
+        date >= self.created() && self.is_scheduled(date) && !self.is_resting(date)
+    }
+    fn is_scheduled(&self, date: NaiveDate) -> bool {
+        self.meta().schedule.includes(date)
+    }
+    fn set_schedule(&mut self, schedule: Schedule) {
+        self.meta_mut().schedule = schedule;
+    }
+    // tags are stored in lowercase, returns false if the habit
+    // already carries `tag`
+    fn add_tag(&mut self, tag: &str) -> bool {
+        let tag = tag.trim().to_lowercase();
+        if self.meta().tags.contains(&tag) {
+            return false;
+        }
+        self.meta_mut().tags.push(tag);
+        true
+    }
+    fn remove_tag(&mut self, tag: &str) -> bool {
+        let tag = tag.trim().to_lowercase();
+        let tags = &mut self.meta_mut().tags;
+        let old_len = tags.len();
+        tags.retain(|t| *t != tag);
+        tags.len() != old_len
+    }
+
+    // fraction of the goal completed on `date`, for habits
+    // that can be partially done
+    fn progress(&self, _date: NaiveDate) -> Option<f64> {
+        None
+    }
+
+    // share of the goal made up by the entry on `date` alone,
+    // drawn as a bar when cells are set to show bars
+    fn fill(&self, _date: NaiveDate) -> Option<f64> {
+        None
+    }
+
+    // habits that are only logged, with no goal to fall short
+    // of, every day counts as done and none is drawn as such
+    fn is_log_only(&self) -> bool {
+        false
+    }
+
+    // whether `date` went past a limit that should not be
+    // crossed, only habits with a cap have one
+    fn over_limit(&self, _date: NaiveDate) -> bool {
+        false
+    }
+
+    // amount tracked on `date`, habits without a quantity
+    // count a day on which the goal was reached as one
+    fn amount(&self, date: NaiveDate) -> u32 {
+        self.reached_goal(date) as u32
+    }
+    // totals of `amount` over the month or year of `date`, for
+    // a `Bit` this is the number of days on which it was done
+    fn sum_in_month(&self, date: NaiveDate) -> u32 {
+        self.tracked_dates()
+            .into_iter()
+            .filter(|d| d.year() == date.year() && d.month() == date.month())
+            .map(|d| self.amount(d))
+            .fold(0, u32::saturating_add)
+    }
+    fn sum_in_year(&self, date: NaiveDate) -> u32 {
+        self.tracked_dates()
+            .into_iter()
+            .filter(|d| d.year() == date.year())
+            .map(|d| self.amount(d))
+            .fold(0, u32::saturating_add)
+    }
+
+    // `amount` summed over every entry, wide enough for years
+    // of large counts
+    fn total(&self) -> u64 {
+        self.tracked_dates()
+            .into_iter()
+            .map(|d| self.amount(d) as u64)
+            .sum()
+    }
+
+    fn raw_entry(&self, date: NaiveDate) -> Option<String> {
+        self.get_by_date(date).map(|v| v.to_raw())
+    }
+    fn accepts_raw(&self, raw: &str) -> bool {
+        Self::HabitType::from_raw(raw).is_some()
+    }
+    fn insert_raw(&mut self, date: NaiveDate, raw: &str) -> bool {
+        match Self::HabitType::from_raw(raw) {
+            Some(v) => {
+                self.insert_entry(date, v);
+                true
+            }
+            None => false,
+        }
+    }
+    // fold the raw `raw` into the entry on `date`
+    fn merge_raw(&mut self, date: NaiveDate, raw: &str) -> bool {
+        let value = match Self::HabitType::from_raw(raw) {
+            Some(v) => v,
+            None => return false,
+        };
+        let merged = match self.get_by_date(date) {
+            Some(old) => old.merge(&value),
+            None => value,
+        };
+        self.insert_entry(date, merged);
+        true
+    }
+
+    // returns false for habits that cannot go below zero
+    fn set_allow_negative(&mut self, _allow: bool) -> bool {
+        false
+    }
+    // returns false for habits without a target total
+    fn set_target(&mut self, _target: Option<(u32, NaiveDate)>) -> bool {
+        false
+    }
+    // whether a target total is still within reach at the
+    // current pace, `None` without a target
+    fn on_track(&self, _today: NaiveDate) -> Option<bool> {
+        None
+    }
+    // returns false for habits that always change by one
+    fn set_step(&mut self, _step: u32) -> bool {
+        false
+    }
+    // returns false for habits without a rolling goal
+    fn set_rolling(&mut self, _rolling: Option<(u32, u32)>) -> bool {
+        false
+    }
+    // span of days whose entries add up towards the goal
+    fn goal_period(&self) -> GoalPeriod {
+        GoalPeriod::Daily
+    }
+    // raw entry that reaches the goal on its own, `None` for
+    // habits that cannot be brought up to their goal
+    fn reaching_raw(&self) -> Option<String> {
+        Some(self.goal().to_string())
+    }
+    fn set_goal_raw(&mut self, raw: &str) -> bool {
+        match Self::HabitType::from_raw(raw) {
+            Some(g) => {
+                self.set_goal(g);
+                true
+            }
+            None => false,
+        }
+    }
+
+    // number of consecutive scheduled days, ending on `date`,
+    // on which the goal was reached
+    fn get_streak(&self, date: NaiveDate) -> u32 {
+        let created = self.created();
+        let mut streak = 0;
+        let mut day = date;
+        while day >= created {
+            if self.is_resting(day) {
+                // rest days neither extend nor break a streak
+            } else if self.reached_goal(day) {
+                streak += 1;
+            } else if self.is_scheduled(day) {
+                break;
+            }
+            match day.pred_opt() {
+                Some(d) => day = d,
+                None => break,
+            }
+        }
+        streak
+    }
+
+    // a streak that ends unless `today` is done, days that are
+    // frozen or off schedule cannot break one
+    fn streak_at_risk(&self, today: NaiveDate) -> bool {
+        let counts = self.is_scheduled(today) && !self.is_resting(today);
+        match today.pred_opt() {
+            Some(yesterday) if counts && !self.reached_goal(today) => {
+                self.get_streak(yesterday) > 0
+            }
+            _ => false,
+        }
+    }
+
+    // longest run of consecutive days on which the goal was
+    // reached, across the entire history of the habit
+    fn longest_streak(&self) -> u32 {
+        let mut dates = self.tracked_dates();
+        dates.sort();
+
+        let (mut longest, mut current) = (0, 0);
+        let mut prev: Option<NaiveDate> = None;
+        // no scheduled day lies between `p` and `d`
+        let adjacent = |p: NaiveDate, d: NaiveDate| {
+            (1..(d - p).num_days())
+                .all(|i| !self.counts_towards_goal(p + chrono::Duration::days(i)))
+        };
+        for d in dates {
+            if self.is_resting(d) {
+                continue;
+            } else if !self.reached_goal(d) {
+                if self.is_scheduled(d) {
+                    current = 0;
+                }
+                continue;
+            } else if matches!(prev, Some(p) if adjacent(p, d)) && current > 0 {
+                current += 1;
+            } else {
+                current = 1;
+            }
+            longest = longest.max(current);
+            prev = Some(d);
+        }
+        longest
+    }
+
+    // fraction of scheduled days in the inclusive range
+    // `from..=to` on which the goal was reached, untracked days
+    // count as misses
+    fn completion_rate(&self, from: NaiveDate, to: NaiveDate) -> f64 {
+        self.checked_completion_rate(from, to).unwrap_or(0.0)
+    }
+    // `None` when no day in the range counts at all
+    fn checked_completion_rate(&self, from: NaiveDate, to: NaiveDate) -> Option<f64> {
+        if from > to {
+            return None;
+        }
+        let days = (0..=(to - from).num_days())
+            .map(|i| from + chrono::Duration::days(i))
+            .filter(|&d| self.counts_towards_goal(d))
+            .collect::<Vec<_>>();
+        if days.is_empty() {
+            return None;
+        }
+        let reached = days.iter().filter(|&&d| self.reached_goal(d)).count();
+        Some(reached as f64 / days.len() as f64)
+    }
+
+    // completion rate of each weekday, starting on monday, from
+    // the first entry up to today, `None` when no such day counts
+    fn weekday_rates(&self) -> [Option<f64>; 7] {
+        let mut reached = [0u32; 7];
+        let mut total = [0u32; 7];
+        let today = utils::today();
+        if let Some(first) = self.tracked_dates().into_iter().min() {
+            for i in 0..=(today - first).num_days() {
+                let d = first + chrono::Duration::days(i);
+                if !self.counts_towards_goal(d) {
+                    continue;
+                }
+                let w = d.weekday().num_days_from_monday() as usize;
+                total[w] += 1;
+                if self.reached_goal(d) {
+                    reached[w] += 1;
+                }
+            }
+        }
+        let mut rates = [None; 7];
+        for w in 0..7 {
+            if total[w] > 0 {
+                rates[w] = Some(reached[w] as f64 / total[w] as f64);
+            }
+        }
+        rates
+    }
 }
 
 #[typetag::serde(tag = "type")]
 pub trait HabitWrapper: erased_serde::Serialize {
     fn remaining(&self, date: NaiveDate) -> u32;
+    fn reached_goal(&self, date: NaiveDate) -> bool;
     fn goal(&self) -> u32;
     fn modify(&mut self, date: NaiveDate, event: TrackEvent);
+    fn sum_in_month(&self, date: NaiveDate) -> u32;
+    fn sum_in_year(&self, date: NaiveDate) -> u32;
+    fn total(&self) -> u64;
+    fn amount(&self, date: NaiveDate) -> u32;
+    fn tracked_dates(&self) -> Vec<NaiveDate>;
+    fn raw_entry(&self, date: NaiveDate) -> Option<String>;
+    fn accepts_raw(&self, raw: &str) -> bool;
+    fn insert_raw(&mut self, date: NaiveDate, raw: &str) -> bool;
+    fn clear_entry(&mut self, date: NaiveDate);
+    fn set_goal_raw(&mut self, raw: &str) -> bool;
+    fn reaching_raw(&self) -> Option<String>;
+    fn set_allow_negative(&mut self, allow: bool) -> bool;
+    fn set_step(&mut self, step: u32) -> bool;
+    fn set_target(&mut self, target: Option<(u32, NaiveDate)>) -> bool;
+    fn set_rolling(&mut self, rolling: Option<(u32, u32)>) -> bool;
+    fn merge_raw(&mut self, date: NaiveDate, raw: &str) -> bool;
+    fn kind(&self) -> &'static str;
+    fn get_streak(&self, date: NaiveDate) -> u32;
+    fn streak_at_risk(&self, today: NaiveDate) -> bool;
+    fn longest_streak(&self) -> u32;
+    fn completion_rate(&self, from: NaiveDate, to: NaiveDate) -> f64;
+    fn weekday_rates(&self) -> [Option<f64>; 7];
     fn draw(&self, printer: &Printer);
     fn on_event(&mut self, event: Event) -> EventResult;
     fn required_size(&mut self, _: Vec2) -> Vec2;
     fn take_focus(&mut self, _: Direction) -> bool;
     fn name(&self) -> String;
+    fn set_name(&mut self, name: &str);
+    fn clone_config(&self, name: &str) -> Box<dyn HabitWrapper>;
+    fn meta(&self) -> &HabitMeta;
+    fn meta_mut(&mut self) -> &mut HabitMeta;
 
     fn set_view_month_offset(&mut self, offset: u32);
     fn view_month_offset(&self) -> u32;
@@ -48,6 +493,30 @@ pub trait HabitWrapper: erased_serde::Serialize {
     fn view_mode(&self) -> ViewMode;
 
     fn is_auto(&self) -> bool;
+
+    fn color(&self) -> Option<Color>;
+    fn set_color(&mut self, color: Option<String>);
+    fn is_archived(&self) -> bool;
+    fn set_archived(&mut self, archived: bool);
+    fn get_note(&self, date: NaiveDate) -> Option<&String>;
+    fn set_note(&mut self, date: NaiveDate, text: &str);
+    fn tags(&self) -> &[String];
+    fn set_schedule(&mut self, schedule: Schedule);
+    fn set_highlights(&mut self, dates: HashSet<NaiveDate>);
+    fn set_cursor(&mut self, date: NaiveDate);
+    fn created(&self) -> NaiveDate;
+    fn set_created(&mut self, date: NaiveDate);
+    fn set_glyphs(&mut self, true_chr: Option<char>, false_chr: Option<char>);
+    fn skips_weekends(&self) -> bool;
+    fn set_skip_weekends(&mut self, skip: bool);
+    fn is_paused(&self, date: NaiveDate) -> bool;
+    fn paused_until(&self) -> Option<NaiveDate>;
+    fn set_pause(&mut self, today: NaiveDate, until: Option<NaiveDate>);
+    fn is_frozen(&self, date: NaiveDate) -> bool;
+    fn freeze(&mut self, date: NaiveDate);
+    fn unfreeze(&mut self, date: NaiveDate);
+    fn add_tag(&mut self, tag: &str) -> bool;
+    fn remove_tag(&mut self, tag: &str) -> bool;
 }
 
 macro_rules! auto_habit_impl {
@@ -72,15 +541,96 @@ macro_rules! auto_habit_impl {
             fn remaining(&self, date: NaiveDate) -> u32 {
                 Habit::remaining(self, date)
             }
+            fn reached_goal(&self, date: NaiveDate) -> bool {
+                Habit::reached_goal(self, date)
+            }
             fn goal(&self) -> u32 {
                 Habit::goal(self)
             }
+            fn sum_in_month(&self, date: NaiveDate) -> u32 {
+                Habit::sum_in_month(self, date)
+            }
+            fn sum_in_year(&self, date: NaiveDate) -> u32 {
+                Habit::sum_in_year(self, date)
+            }
+            fn total(&self) -> u64 {
+                Habit::total(self)
+            }
+            fn amount(&self, date: NaiveDate) -> u32 {
+                Habit::amount(self, date)
+            }
             fn modify(&mut self, date: NaiveDate, event: TrackEvent) {
                 Habit::modify(self, date, event);
             }
+            fn tracked_dates(&self) -> Vec<NaiveDate> {
+                Habit::tracked_dates(self)
+            }
+            fn raw_entry(&self, date: NaiveDate) -> Option<String> {
+                Habit::raw_entry(self, date)
+            }
+            fn accepts_raw(&self, raw: &str) -> bool {
+                Habit::accepts_raw(self, raw)
+            }
+            fn insert_raw(&mut self, date: NaiveDate, raw: &str) -> bool {
+                Habit::insert_raw(self, date, raw)
+            }
+            fn clear_entry(&mut self, date: NaiveDate) {
+                Habit::clear_entry(self, date)
+            }
+            fn set_goal_raw(&mut self, raw: &str) -> bool {
+                Habit::set_goal_raw(self, raw)
+            }
+            fn reaching_raw(&self) -> Option<String> {
+                Habit::reaching_raw(self)
+            }
+            fn set_allow_negative(&mut self, allow: bool) -> bool {
+                Habit::set_allow_negative(self, allow)
+            }
+            fn set_target(&mut self, target: Option<(u32, NaiveDate)>) -> bool {
+                Habit::set_target(self, target)
+            }
+            fn set_step(&mut self, step: u32) -> bool {
+                Habit::set_step(self, step)
+            }
+            fn set_rolling(&mut self, rolling: Option<(u32, u32)>) -> bool {
+                Habit::set_rolling(self, rolling)
+            }
+            fn merge_raw(&mut self, date: NaiveDate, raw: &str) -> bool {
+                Habit::merge_raw(self, date, raw)
+            }
+            fn kind(&self) -> &'static str {
+                stringify!($struct_name)
+            }
+            fn get_streak(&self, date: NaiveDate) -> u32 {
+                Habit::get_streak(self, date)
+            }
+            fn streak_at_risk(&self, today: NaiveDate) -> bool {
+                Habit::streak_at_risk(self, today)
+            }
+            fn longest_streak(&self) -> u32 {
+                Habit::longest_streak(self)
+            }
+            fn completion_rate(&self, from: NaiveDate, to: NaiveDate) -> f64 {
+                Habit::completion_rate(self, from, to)
+            }
+            fn weekday_rates(&self) -> [Option<f64>; 7] {
+                Habit::weekday_rates(self)
+            }
             fn name(&self) -> String {
                 Habit::name(self)
             }
+            fn set_name(&mut self, name: &str) {
+                Habit::set_name(self, name)
+            }
+            fn clone_config(&self, name: &str) -> Box<dyn HabitWrapper> {
+                Box::new(Habit::clone_config(self, name))
+            }
+            fn meta(&self) -> &HabitMeta {
+                Habit::meta(self)
+            }
+            fn meta_mut(&mut self) -> &mut HabitMeta {
+                Habit::meta_mut(self)
+            }
             fn set_view_month_offset(&mut self, offset: u32) {
                 Habit::set_view_month_offset(self, offset)
             }
@@ -96,9 +646,241 @@ macro_rules! auto_habit_impl {
             fn is_auto(&self) -> bool {
                 Habit::is_auto(self)
             }
+            fn color(&self) -> Option<Color> {
+                Habit::color(self)
+            }
+            fn set_color(&mut self, color: Option<String>) {
+                Habit::set_color(self, color)
+            }
+            fn is_archived(&self) -> bool {
+                Habit::is_archived(self)
+            }
+            fn set_archived(&mut self, archived: bool) {
+                Habit::set_archived(self, archived)
+            }
+            fn get_note(&self, date: NaiveDate) -> Option<&String> {
+                Habit::get_note(self, date)
+            }
+            fn set_note(&mut self, date: NaiveDate, text: &str) {
+                Habit::set_note(self, date, text)
+            }
+            fn tags(&self) -> &[String] {
+                Habit::tags(self)
+            }
+            fn set_schedule(&mut self, schedule: Schedule) {
+                Habit::set_schedule(self, schedule)
+            }
+            fn set_highlights(&mut self, dates: HashSet<NaiveDate>) {
+                Habit::set_highlights(self, dates)
+            }
+            fn set_cursor(&mut self, date: NaiveDate) {
+                Habit::set_cursor(self, date)
+            }
+            fn created(&self) -> NaiveDate {
+                Habit::created(self)
+            }
+            fn set_created(&mut self, date: NaiveDate) {
+                Habit::set_created(self, date)
+            }
+            fn set_glyphs(&mut self, true_chr: Option<char>, false_chr: Option<char>) {
+                Habit::set_glyphs(self, true_chr, false_chr)
+            }
+            fn skips_weekends(&self) -> bool {
+                Habit::skips_weekends(self)
+            }
+            fn set_skip_weekends(&mut self, skip: bool) {
+                Habit::set_skip_weekends(self, skip)
+            }
+            fn is_paused(&self, date: NaiveDate) -> bool {
+                Habit::is_paused(self, date)
+            }
+            fn paused_until(&self) -> Option<NaiveDate> {
+                Habit::paused_until(self)
+            }
+            fn set_pause(&mut self, today: NaiveDate, until: Option<NaiveDate>) {
+                Habit::set_pause(self, today, until)
+            }
+            fn is_frozen(&self, date: NaiveDate) -> bool {
+                Habit::is_frozen(self, date)
+            }
+            fn freeze(&mut self, date: NaiveDate) {
+                Habit::freeze(self, date)
+            }
+            fn unfreeze(&mut self, date: NaiveDate) {
+                Habit::unfreeze(self, date)
+            }
+            fn add_tag(&mut self, tag: &str) -> bool {
+                Habit::add_tag(self, tag)
+            }
+            fn remove_tag(&mut self, tag: &str) -> bool {
+                Habit::remove_tag(self, tag)
+            }
         }
     };
 }
 
 auto_habit_impl!(Count);
 auto_habit_impl!(Bit);
+auto_habit_impl!(FloatCount);
+auto_habit_impl!(Duration);
+auto_habit_impl!(Capped);
+auto_habit_impl!(Checklist);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd(2026, 1, d)
+    }
+
+    // done on every day in `done`
+    fn bit(done: &[u32]) -> Box<dyn HabitWrapper> {
+        let mut h = Bit::new("walk", false);
+        for &d in done {
+            h.insert_entry(day(d), true.into());
+        }
+        Box::new(h)
+    }
+
+    #[test]
+    fn longest_streak_of_an_empty_habit() {
+        assert_eq!(bit(&[]).longest_streak(), 0);
+    }
+
+    #[test]
+    fn longest_streak_of_a_single_day() {
+        assert_eq!(bit(&[7]).longest_streak(), 1);
+    }
+
+    #[test]
+    fn longest_streak_picks_the_longer_earlier_run() {
+        let h = bit(&[1, 2, 3, 4, 8, 9]);
+        assert_eq!(h.longest_streak(), 4);
+        assert_eq!(h.get_streak(day(9)), 2);
+    }
+
+    #[test]
+    fn longest_streak_breaks_on_a_missed_day() {
+        let mut h = Bit::new("walk", false);
+        for &d in &[1, 2, 4, 5] {
+            h.insert_entry(day(d), true.into());
+        }
+        h.insert_entry(day(3), false.into());
+        assert_eq!(Habit::longest_streak(&h), 2);
+    }
+
+    #[test]
+    fn completion_rate_counts_every_day_in_the_range() {
+        let mut h = bit(&[2, 5, 9]);
+        h.set_created(day(1));
+        assert_eq!(h.completion_rate(day(1), day(10)), 0.3);
+        assert_eq!(h.completion_rate(day(11), day(20)), 0.0);
+        assert_eq!(h.completion_rate(day(10), day(1)), 0.0);
+    }
+
+    #[test]
+    fn weekday_habit_keeps_its_streak_over_the_weekend() {
+        use chrono::Weekday::*;
+        // thursday the first to monday the fifth
+        let mut h = bit(&[1, 2, 5]);
+        h.set_schedule(Schedule::Weekdays(vec![Mon, Tue, Wed, Thu, Fri]));
+        assert_eq!(h.get_streak(day(5)), 3);
+        assert_eq!(h.longest_streak(), 3);
+        h.set_schedule(Schedule::EveryDay);
+        assert_eq!(h.get_streak(day(5)), 1);
+    }
+
+    #[test]
+    fn sums_are_bucketed_by_month_and_year() {
+        let date = |y, m, d| NaiveDate::from_ymd(y, m, d);
+        let mut c = Count::new("water", 3, false);
+        c.insert_entry(date(2026, 1, 30), 2);
+        c.insert_entry(date(2026, 1, 31), 3);
+        c.insert_entry(date(2026, 2, 1), 4);
+        c.insert_entry(date(2025, 12, 31), 5);
+        let c: Box<dyn HabitWrapper> = Box::new(c);
+        assert_eq!(c.sum_in_month(date(2026, 1, 1)), 5);
+        assert_eq!(c.sum_in_month(date(2026, 2, 15)), 4);
+        assert_eq!(c.sum_in_month(date(2026, 3, 1)), 0);
+        assert_eq!(c.sum_in_year(date(2026, 6, 1)), 9);
+        assert_eq!(c.sum_in_year(date(2025, 6, 1)), 5);
+
+        // a yes/no habit counts the days it was done
+        let mut b = bit(&[30, 31]);
+        b.insert_raw(date(2026, 2, 1), "1");
+        b.insert_raw(date(2026, 2, 2), "0");
+        assert_eq!(b.sum_in_month(date(2026, 1, 1)), 2);
+        assert_eq!(b.sum_in_month(date(2026, 2, 1)), 1);
+        assert_eq!(b.sum_in_year(date(2026, 1, 1)), 3);
+    }
+
+    #[test]
+    fn streak_survives_a_frozen_day() {
+        let mut h = bit(&[1, 2, 4, 5]);
+        assert_eq!(h.get_streak(day(5)), 2);
+        h.freeze(day(3));
+        assert_eq!(h.get_streak(day(5)), 4);
+        assert_eq!(h.longest_streak(), 4);
+        assert_eq!(h.completion_rate(day(1), day(5)), 1.0);
+    }
+
+    #[test]
+    fn completion_rate_starts_on_the_creation_date() {
+        // without a saved date the first entry stands in
+        let mut h = bit(&[6, 7, 8, 10]);
+        assert_eq!(h.created(), day(6));
+        assert_eq!(h.completion_rate(day(1), day(10)), 0.8);
+        h.set_created(day(1));
+        assert_eq!(h.completion_rate(day(1), day(10)), 0.4);
+        h.set_created(day(8));
+        assert_eq!(h.created(), day(6));
+    }
+
+    #[test]
+    fn total_spans_every_year() {
+        let date = |y, m, d| NaiveDate::from_ymd(y, m, d);
+        let mut c = Count::new("pages", 10, false);
+        c.insert_entry(date(2024, 2, 29), 12);
+        c.insert_entry(date(2025, 7, 1), 30);
+        c.insert_entry(date(2026, 1, 1), 8);
+        let c: Box<dyn HabitWrapper> = Box::new(c);
+        assert_eq!(c.total(), 50);
+
+        let mut b = bit(&[1]);
+        b.insert_raw(date(2024, 5, 5), "1");
+        b.insert_raw(date(2025, 5, 5), "0");
+        assert_eq!(b.total(), 2);
+    }
+
+    #[test]
+    fn streak_at_risk_until_today_is_done() {
+        let mut h = bit(&[3, 4]);
+        assert!(h.streak_at_risk(day(5)));
+        assert!(!h.streak_at_risk(day(7)));
+        assert!(!bit(&[]).streak_at_risk(day(5)));
+        h.insert_raw(day(5), "1");
+        assert!(!h.streak_at_risk(day(5)));
+
+        let mut frozen = bit(&[3, 4]);
+        frozen.freeze(day(5));
+        assert!(!frozen.streak_at_risk(day(5)));
+    }
+
+    #[test]
+    fn paused_days_are_neither_done_nor_missed() {
+        let mut h = bit(&[1, 2, 3, 4, 8]);
+        assert_eq!(h.get_streak(day(8)), 1);
+        h.set_pause(day(5), Some(day(8)));
+        assert!(h.is_paused(day(7)));
+        assert!(!h.is_paused(day(8)));
+        assert_eq!(h.get_streak(day(8)), 5);
+        assert_eq!(h.completion_rate(day(1), day(8)), 1.0);
+
+        // days spent paused stay neutral once the pause ends
+        h.set_pause(day(8), None);
+        assert!(!h.is_paused(day(6)));
+        assert_eq!(h.get_streak(day(8)), 5);
+        assert_eq!(h.completion_rate(day(1), day(8)), 1.0);
+    }
+}
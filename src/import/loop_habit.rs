@@ -0,0 +1,117 @@
+use chrono::NaiveDate;
+
+use crate::export::ImportError;
+
+// Loop Habit Tracker exports every habit as a column of
+// Checkmarks.csv:
+//
+//     Date,Meditate,Pushups,
+//     2020-07-06,2,25000,
+//     2020-07-05,0,-1,
+//
+// a checkmark is 2 when ticked by hand, 1 when implied by the
+// habit's frequency, 0 when not done and -1 when unknown. numeric
+// habits store thousandths instead.
+//
+// a column with nothing above 2 becomes a `Bit`, done on days
+// marked 1 or 2. any other column becomes a `Count`, divided by
+// 1000 if it holds values of 1000 or more. days at or below 0 are
+// left untracked.
+pub struct LoopHabit {
+    pub name: String,
+    pub is_bit: bool,
+    pub entries: Vec<(NaiveDate, u32)>,
+}
+
+pub fn parse_loop(data: &str) -> Result<Vec<LoopHabit>, ImportError> {
+    let mut lines = data
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty());
+    let names: Vec<String> = match lines.next() {
+        Some((_, header)) if header.trim().to_lowercase().starts_with("date") => header
+            .split(',')
+            .skip(1)
+            .map(|n| n.trim().to_owned())
+            .filter(|n| !n.is_empty())
+            .collect(),
+        Some((idx, _)) => return Err(ImportError::MissingField(idx + 1)),
+        None => return Ok(vec![]),
+    };
+
+    let mut columns: Vec<Vec<(NaiveDate, i64)>> = vec![vec![]; names.len()];
+    for (idx, line) in lines {
+        let line_nr = idx + 1;
+        let mut fields = line.split(',').map(|f| f.trim());
+        let date = fields.next().unwrap_or_default();
+        let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|_| ImportError::InvalidDate(line_nr))?;
+        for column in columns.iter_mut() {
+            let value = match fields.next() {
+                Some("") => continue,
+                Some(v) => v
+                    .parse::<i64>()
+                    .map_err(|_| ImportError::InvalidValue(line_nr))?,
+                None => return Err(ImportError::MissingField(line_nr)),
+            };
+            column.push((date, value));
+        }
+    }
+
+    Ok(names
+        .into_iter()
+        .zip(columns)
+        .map(|(name, column)| {
+            let max = column.iter().map(|&(_, v)| v).max().unwrap_or(0);
+            let is_bit = max <= 2;
+            let scale = if !is_bit && max >= 1000 { 1000 } else { 1 };
+            let entries = column
+                .into_iter()
+                .filter(|&(_, v)| v > 0)
+                .map(|(d, v)| {
+                    let value = if is_bit { 1 } else { (v + scale / 2) / scale };
+                    (d, value as u32)
+                })
+                .collect();
+            LoopHabit {
+                name,
+                is_bit,
+                entries,
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHECKMARKS: &str = "Date,Meditate,Pushups,
+2020-07-06,2,25000,
+2020-07-05,0,-1,
+2020-07-04,1,12400,
+";
+
+    #[test]
+    fn columns_become_habits() {
+        let date = |d| NaiveDate::from_ymd(2020, 7, d);
+        let habits = parse_loop(CHECKMARKS).unwrap();
+        assert_eq!(habits.len(), 2);
+
+        assert_eq!(habits[0].name, "Meditate");
+        assert!(habits[0].is_bit);
+        assert_eq!(habits[0].entries, vec![(date(6), 1), (date(4), 1)]);
+
+        assert_eq!(habits[1].name, "Pushups");
+        assert!(!habits[1].is_bit);
+        assert_eq!(habits[1].entries, vec![(date(6), 25), (date(4), 12)]);
+    }
+
+    #[test]
+    fn bad_rows_are_refused() {
+        assert!(parse_loop("Meditate\n2020-07-06,2\n").is_err());
+        assert!(parse_loop("Date,Meditate\n07/06/2020,2\n").is_err());
+        assert!(parse_loop("Date,Meditate\n2020-07-06,yes\n").is_err());
+        assert!(parse_loop("").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,2 @@
+mod loop_habit;
+pub use loop_habit::parse_loop;
@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::io::prelude::*;
+
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use serde_json::{Number, Value};
+
+use crate::habit::HabitWrapper;
+use crate::utils;
+
+// one change to one day of one habit, `value` is null once
+// the entry was cleared
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub habit: String,
+    pub date: NaiveDate,
+    pub value: Value,
+    pub ts: i64,
+}
+
+impl JournalEntry {
+    fn new(habit: &str, date: NaiveDate, raw: Option<&str>) -> Self {
+        // raw entries are numbers for every kind of habit, keep
+        // anything else as text rather than losing it
+        let value = match raw {
+            Some(r) => match r.parse::<Number>() {
+                Ok(n) => Value::Number(n),
+                Err(_) => Value::String(r.to_owned()),
+            },
+            None => Value::Null,
+        };
+        JournalEntry {
+            habit: habit.to_owned(),
+            date,
+            value,
+            ts: Local::now().timestamp(),
+        }
+    }
+
+    fn raw(&self) -> Option<String> {
+        match &self.value {
+            Value::Number(n) => Some(n.to_string()),
+            Value::String(s) => Some(s.clone()),
+            _ => None,
+        }
+    }
+}
+
+// `habit` taking the name `renamed`, entries journaled under
+// the old name belong to it from then on
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JournalRename {
+    pub habit: String,
+    pub renamed: String,
+    pub ts: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+enum JournalLine {
+    Entry(JournalEntry),
+    Rename(JournalRename),
+}
+
+// append the entry of `habit` on `date` to the journal of
+// `profile`, it is written out straight away
+pub fn record(profile: Option<&str>, habit: &str, date: NaiveDate, raw: Option<&str>) {
+    append(
+        profile,
+        &JournalLine::Entry(JournalEntry::new(habit, date, raw)),
+    );
+}
+
+pub fn record_rename(profile: Option<&str>, habit: &str, renamed: &str) {
+    append(
+        profile,
+        &JournalLine::Rename(JournalRename {
+            habit: habit.to_owned(),
+            renamed: renamed.to_owned(),
+            ts: Local::now().timestamp(),
+        }),
+    );
+}
+
+fn append(profile: Option<&str>, line: &JournalLine) {
+    let line = match serde_json::to_string(line) {
+        Ok(l) => l,
+        Err(_) => return,
+    };
+    if let Ok(mut f) = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(utils::journal_file(profile))
+    {
+        writeln!(f, "{}", line);
+        f.flush();
+    }
+}
+
+// apply every change in `data` to the habit of the same name, in
+// order, so habits without entries end up as they were when the
+// journal was written. A habit renamed later on is found under
+// the name it has now. Lines that cannot be read, such as one cut
+// short by a crash, are skipped. Gives the number of changes
+// applied and skipped
+pub fn replay(habits: &mut [Box<dyn HabitWrapper>], data: &str) -> (usize, usize) {
+    let mut lines = data
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| serde_json::from_str::<JournalLine>(l).ok())
+        .collect::<Vec<_>>();
+
+    // walking back from the end, the name each name written so
+    // far ends up with
+    let mut current = HashMap::new();
+    for line in lines.iter_mut().rev() {
+        match line {
+            Some(JournalLine::Rename(r)) => {
+                let last = current
+                    .remove(&r.renamed)
+                    .unwrap_or_else(|| r.renamed.clone());
+                current.insert(r.habit.clone(), last);
+            }
+            Some(JournalLine::Entry(e)) => {
+                if let Some(name) = current.get(&e.habit) {
+                    e.habit = name.clone();
+                }
+            }
+            None => {}
+        }
+    }
+
+    let (mut applied, mut skipped) = (0, 0);
+    for line in lines {
+        let entry = match line {
+            Some(JournalLine::Entry(e)) => e,
+            Some(JournalLine::Rename(_)) => continue,
+            None => {
+                skipped += 1;
+                continue;
+            }
+        };
+        let habit = match habits.iter_mut().find(|h| h.name() == entry.habit) {
+            Some(h) => h,
+            None => {
+                skipped += 1;
+                continue;
+            }
+        };
+        match entry.raw() {
+            Some(raw) if habit.insert_raw(entry.date, &raw) => applied += 1,
+            Some(_) => skipped += 1,
+            None => {
+                habit.clear_entry(entry.date);
+                applied += 1;
+            }
+        }
+    }
+    (applied, skipped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::habit::{Bit, Count};
+
+    #[test]
+    fn replay_skips_a_line_cut_short() {
+        let day = |d| NaiveDate::from_ymd(2026, 1, d);
+        let mut habits: Vec<Box<dyn HabitWrapper>> = vec![
+            Box::new(Count::new("water", 3, false)),
+            Box::new(Bit::new("walk", false)),
+        ];
+        let journal = r#"{"habit":"water","date":"2026-01-01","value":2,"ts":0}
+{"habit":"walk","date":"2026-01-01","value":1,"ts":0}
+{"habit":"water","date":"2026-01-01","value":null,"ts":0}
+{"habit":"water","date":"2026-01-02","value":5,"ts":0}
+{"habit":"gone","date":"2026-01-02","value":5,"ts":0}
+{"habit":"walk","date":"2026-01-0"#;
+        assert_eq!(replay(&mut habits, journal), (4, 2));
+        assert_eq!(habits[0].raw_entry(day(1)), None);
+        assert_eq!(habits[0].raw_entry(day(2)), Some("5".to_owned()));
+        assert_eq!(habits[1].raw_entry(day(1)), Some("1".to_owned()));
+    }
+
+    #[test]
+    fn replay_follows_renames() {
+        let day = |d| NaiveDate::from_ymd(2026, 1, d);
+        let mut habits: Vec<Box<dyn HabitWrapper>> = vec![
+            Box::new(Count::new("tea", 3, false)),
+            Box::new(Bit::new("water", false)),
+        ];
+        let journal = r#"{"habit":"water","date":"2026-01-01","value":2,"ts":0}
+{"habit":"water","renamed":"drink","ts":0}
+{"habit":"drink","date":"2026-01-02","value":3,"ts":0}
+{"habit":"walk","date":"2026-01-01","value":1,"ts":0}
+{"habit":"walk","renamed":"water","ts":0}
+{"habit":"water","date":"2026-01-02","value":1,"ts":0}
+{"habit":"drink","renamed":"tea","ts":0}"#;
+        assert_eq!(replay(&mut habits, journal), (4, 0));
+        assert_eq!(habits[0].raw_entry(day(1)), Some("2".to_owned()));
+        assert_eq!(habits[0].raw_entry(day(2)), Some("3".to_owned()));
+        assert_eq!(habits[1].raw_entry(day(1)), Some("1".to_owned()));
+        assert_eq!(habits[1].raw_entry(day(2)), Some("1".to_owned()));
+    }
+}
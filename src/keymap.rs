@@ -0,0 +1,438 @@
+use std::collections::BTreeMap;
+
+use cursive::event::{Event, Key};
+
+use crate::CONFIGURATION;
+
+pub struct Action {
+    // the name used in the `[keybindings]` section
+    pub name: &'static str,
+    pub key: &'static str,
+    // keys that run the action whatever it is bound to
+    pub also: &'static [&'static str],
+    // refused in read-only mode
+    pub edits: bool,
+    pub about: &'static str,
+}
+
+// every action the board reacts to, in the order `:help`
+// lists them. each can be moved to another key from the
+// `[keybindings]` section of config.toml
+pub const ACTIONS: &[Action] = &[
+    Action {
+        name: "focus_left",
+        key: "h",
+        also: &["Left", "S-Tab"],
+        edits: false,
+        about: "focus the habit to the left",
+    },
+    Action {
+        name: "focus_right",
+        key: "l",
+        also: &["Right", "Tab"],
+        edits: false,
+        about: "focus the habit to the right",
+    },
+    Action {
+        name: "focus_up",
+        key: "k",
+        also: &["Up"],
+        edits: false,
+        about: "focus the habit above",
+    },
+    Action {
+        name: "focus_down",
+        key: "j",
+        also: &["Down"],
+        edits: false,
+        about: "focus the habit below",
+    },
+    Action {
+        name: "increment",
+        key: "n",
+        also: &[],
+        edits: true,
+        about: "track the day",
+    },
+    Action {
+        name: "value",
+        key: "Enter",
+        also: &[],
+        edits: true,
+        about: "ask for the value of the day, yes/no habits are tracked",
+    },
+    Action {
+        name: "decrement",
+        key: "p",
+        also: &["Backspace"],
+        edits: true,
+        about: "take back a track of the day",
+    },
+    Action {
+        name: "clear",
+        key: "x",
+        also: &[],
+        edits: true,
+        about: "clear the day",
+    },
+    Action {
+        name: "freeze",
+        key: "f",
+        also: &[],
+        edits: true,
+        about: "freeze the day, it is neither done nor missed",
+    },
+    Action {
+        name: "copy_previous",
+        key: "y",
+        also: &[],
+        edits: true,
+        about: "copy the entry of the day before",
+    },
+    Action {
+        name: "note",
+        key: "i",
+        also: &[],
+        edits: true,
+        about: "write a note for the day",
+    },
+    Action {
+        name: "complete_today",
+        key: "A",
+        also: &[],
+        edits: true,
+        about: "bring every habit up to its goal for today",
+    },
+    Action {
+        name: "cursor_back",
+        key: "H",
+        also: &[],
+        edits: false,
+        about: "move the day cursor back",
+    },
+    Action {
+        name: "cursor_forward",
+        key: "L",
+        also: &[],
+        edits: false,
+        about: "move the day cursor forth",
+    },
+    Action {
+        name: "today",
+        key: "T",
+        also: &["}"],
+        edits: false,
+        about: "go back to today",
+    },
+    Action {
+        name: "month_prev",
+        key: "[",
+        also: &[],
+        edits: false,
+        about: "show the month before",
+    },
+    Action {
+        name: "month_next",
+        key: "]",
+        also: &[],
+        edits: false,
+        about: "show the month after",
+    },
+    Action {
+        name: "cycle_view",
+        key: "v",
+        also: &[],
+        edits: false,
+        about: "cycle the view of the focused habit",
+    },
+    Action {
+        name: "week_view",
+        key: "V",
+        also: &[],
+        edits: false,
+        about: "show every habit by week",
+    },
+    Action {
+        name: "move_up",
+        key: "K",
+        also: &[],
+        edits: true,
+        about: "move the focused habit up",
+    },
+    Action {
+        name: "move_down",
+        key: "J",
+        also: &[],
+        edits: true,
+        about: "move the focused habit down",
+    },
+    Action {
+        name: "select",
+        key: "Space",
+        also: &[],
+        edits: false,
+        about: "select the focused habit",
+    },
+    Action {
+        name: "clear_selection",
+        key: "Esc",
+        also: &[],
+        edits: false,
+        about: "clear the selection and go back to the day view",
+    },
+    Action {
+        name: "jump",
+        key: "/",
+        also: &[],
+        edits: false,
+        about: "jump to a habit by typing the start of its name",
+    },
+    Action {
+        name: "archived",
+        key: "a",
+        also: &[],
+        edits: false,
+        about: "show or hide archived habits",
+    },
+    Action {
+        name: "delete",
+        key: "d",
+        also: &[],
+        edits: true,
+        about: "delete the focused habit",
+    },
+    Action {
+        name: "detail",
+        key: "o",
+        also: &[],
+        edits: false,
+        about: "open the focused habit in detail",
+    },
+    Action {
+        name: "stats",
+        key: "S",
+        also: &[],
+        edits: false,
+        about: "statistics of the month",
+    },
+    Action {
+        name: "undo",
+        key: "u",
+        also: &[],
+        edits: true,
+        about: "undo",
+    },
+    Action {
+        name: "redo",
+        key: "C-r",
+        also: &[],
+        edits: true,
+        about: "redo",
+    },
+    Action {
+        name: "clear_message",
+        key: "C-l",
+        also: &[],
+        edits: false,
+        about: "clear the message line",
+    },
+    Action {
+        name: "command",
+        key: ":",
+        also: &[],
+        edits: false,
+        about: "type a command",
+    },
+    Action {
+        name: "help",
+        key: "?",
+        also: &[],
+        edits: false,
+        about: "this help",
+    },
+    Action {
+        name: "quit",
+        key: "q",
+        also: &[],
+        edits: false,
+        about: "save and quit",
+    },
+];
+
+pub struct Binding {
+    pub action: &'static str,
+    // the key as written in the config, for `:help`
+    pub key: String,
+    pub event: Event,
+}
+
+// a single character, a key name such as `Enter` or `Left`,
+// or either of those after `C-` for control
+pub fn parse_key(s: &str) -> Option<Event> {
+    let mut chars = s.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(Event::Char(c));
+    }
+    if let Some(rest) = s.strip_prefix("C-") {
+        let mut chars = rest.chars();
+        return match (chars.next(), chars.next()) {
+            (Some(c), None) if c.is_ascii_alphabetic() => {
+                Some(Event::CtrlChar(c.to_ascii_lowercase()))
+            }
+            _ => None,
+        };
+    }
+    let key = match s.to_lowercase().as_str() {
+        "space" => return Some(Event::Char(' ')),
+        "enter" => Key::Enter,
+        "backspace" => Key::Backspace,
+        "tab" => Key::Tab,
+        "s-tab" => return Some(Event::Shift(Key::Tab)),
+        "esc" => Key::Esc,
+        "left" => Key::Left,
+        "right" => Key::Right,
+        "up" => Key::Up,
+        "down" => Key::Down,
+        "del" | "delete" => Key::Del,
+        "home" => Key::Home,
+        "end" => Key::End,
+        _ => return None,
+    };
+    Some(Event::Key(key))
+}
+
+fn action(name: &str) -> Option<&'static Action> {
+    ACTIONS.iter().find(|a| a.name == name)
+}
+
+pub fn default_key(name: &str) -> Option<Event> {
+    action(name).and_then(|a| parse_key(a.key))
+}
+
+// bindings for every action named in `table`, names and keys
+// that mean nothing are left out and reported
+pub fn bindings(table: BTreeMap<String, String>, warnings: &mut Vec<String>) -> Vec<Binding> {
+    let mut bound = Vec::new();
+    for (action, key) in table {
+        let action = match self::action(&action) {
+            Some(a) => a.name,
+            None => {
+                warnings.push(format!("Unknown key binding `{}`", action));
+                continue;
+            }
+        };
+        match parse_key(key.trim()) {
+            Some(event) => bound.push(Binding { action, key, event }),
+            None => warnings.push(format!("Invalid key `{}` for {}", key, action)),
+        }
+    }
+    bound
+}
+
+// the key that runs `action`, its own unless it was moved
+pub fn key_for(action: &str) -> Event {
+    CONFIGURATION
+        .keybindings
+        .iter()
+        .find(|b| b.action == action)
+        .map(|b| b.event.clone())
+        .or_else(|| default_key(action))
+        .unwrap_or(Event::Unknown(Vec::new()))
+}
+
+// keys that run `name` as `:help` shows them, the moved key
+// in place of its own
+pub fn key_names(name: &str) -> String {
+    let a = match action(name) {
+        Some(a) => a,
+        None => return String::new(),
+    };
+    let key = CONFIGURATION
+        .keybindings
+        .iter()
+        .find(|b| b.action == name)
+        .map_or(a.key, |b| b.key.trim());
+    let mut names = vec![key];
+    names.extend(a.also);
+    names.join(" ")
+}
+
+// whether the action `name` changes habits
+pub fn edits(name: &str) -> bool {
+    action(name).map_or(false, |a| a.edits)
+}
+
+// the action the key `e` runs. an action moved to another key
+// no longer answers to its own, the keys in `also` always do
+pub fn action_for(e: &Event) -> Option<&'static str> {
+    action_with(&CONFIGURATION.keybindings, e)
+}
+
+fn action_with(bindings: &[Binding], e: &Event) -> Option<&'static str> {
+    if let Some(b) = bindings.iter().find(|b| &b.event == e) {
+        return Some(b.action);
+    }
+    let is = |k: &str| parse_key(k).as_ref() == Some(e);
+    ACTIONS
+        .iter()
+        .find(|a| {
+            let moved = bindings.iter().any(|b| b.action == a.name);
+            (!moved && is(a.key)) || a.also.iter().any(|k| is(k))
+        })
+        .map(|a| a.name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::habit::{Count, Habit};
+    use crate::views::ShadowView;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn remapped_increment_key_increments() {
+        let mut table = BTreeMap::new();
+        table.insert("increment".to_owned(), "+".to_owned());
+        table.insert("levitate".to_owned(), "z".to_owned());
+        table.insert("decrement".to_owned(), "C-".to_owned());
+        let mut warnings = Vec::new();
+        let bound = bindings(table, &mut warnings);
+        assert_eq!(bound.len(), 1);
+        assert_eq!(warnings.len(), 2);
+
+        assert_eq!(action_with(&bound, &Event::Char('+')), Some("increment"));
+        assert_eq!(action_with(&bound, &Event::Char('-')), None);
+        assert_eq!(action_with(&[], &Event::Char('n')), Some("increment"));
+
+        // pressed the way the board passes them on, the old key
+        // is free once the action moves
+        let d = NaiveDate::from_ymd(2026, 5, 4);
+        let mut c = Count::new("water", 3, false);
+        Habit::set_cursor(&mut c, d);
+        for key in &['+', 'n', 'n'] {
+            if let Some(action) = action_with(&bound, &Event::Char(*key)) {
+                ShadowView::on_event(&mut c, default_key(action).unwrap());
+            }
+        }
+        assert_eq!(c.get_by_date(d), Some(&1));
+    }
+
+    #[test]
+    fn help_lists_every_action_under_a_key_that_parses() {
+        let help = crate::command::help_text();
+        for a in ACTIONS {
+            assert!(parse_key(a.key).is_some(), "{}", a.name);
+            assert!(a.also.iter().all(|k| parse_key(k).is_some()), "{}", a.name);
+            let line = format!("{}  ", key_names(a.name));
+            assert!(
+                help.lines()
+                    .any(|l| l.starts_with(&line) && l.ends_with(a.about)),
+                "{}",
+                a.name
+            );
+        }
+        assert!(!edits("focus_left"));
+        assert!(edits("decrement"));
+        assert_eq!(action_for(&Event::Shift(Key::Tab)), Some("focus_left"));
+    }
+}
@@ -2,18 +2,29 @@
 
 mod app;
 mod command;
+mod export;
 mod habit;
+mod import;
+mod journal;
+mod keymap;
+mod save;
 mod theme;
 mod utils;
 mod views;
 
+use std::process;
+use std::thread;
+use std::time::Duration;
+
 use crate::app::App;
 use crate::command::{open_command_window, Command};
 use crate::utils::{load_configuration_file, AppConfig};
+use crate::views::open_report_window;
 
 use clap::{App as ClapApp, Arg};
 use cursive::termion;
 use cursive::views::{LinearLayout, NamedView};
+use cursive::Cursive;
 use lazy_static::lazy_static;
 
 lazy_static! {
@@ -33,33 +44,100 @@ fn main() {
                 .value_name("CMD")
                 .help("run a dijo command"),
         )
+        .arg(
+            Arg::with_name("profile")
+                .short("p")
+                .long("profile")
+                .takes_value(true)
+                .value_name("NAME")
+                .help("use the habits of a separate profile"),
+        )
+        .arg(
+            Arg::with_name("data-dir")
+                .long("data-dir")
+                .takes_value(true)
+                .value_name("PATH")
+                .help("keep save files in PATH"),
+        )
+        .arg(
+            Arg::with_name("readonly")
+                .long("readonly")
+                .help("start without allowing changes to habits"),
+        )
         .get_matches();
+    let readonly = matches.is_present("readonly");
+    if let Some(dir) = matches.value_of("data-dir") {
+        utils::set_data_dir(dir);
+    }
+    let profile = matches.value_of("profile").map(|p| p.to_string());
+    if let Some(p) = &profile {
+        if !utils::valid_profile(p) {
+            eprintln!("Invalid profile name `{}`", p);
+            return;
+        }
+    }
     if let Some(c) = matches.value_of("command") {
         let command = Command::from_string(c);
         match command {
             Ok(Command::TrackUp(_)) | Ok(Command::TrackDown(_)) => {
-                let mut app = App::load_state();
+                let mut app = load_or_exit(profile);
+                app.set_readonly(readonly);
                 app.parse_command(command);
-                app.save_state();
+                if let Err(e) = app.save_state() {
+                    eprintln!("{}", e);
+                    process::exit(1);
+                }
             }
+            Ok(Command::List(json)) => match load_or_exit(profile).list(json) {
+                Ok(list) => println!("{}", list.trim_end()),
+                Err(e) => eprintln!("{}", e),
+            },
             Err(e) => {
                 eprintln!("{}", e);
             }
             _ => eprintln!(
-                "Commands other than `track-up` and `track-down` are currently not supported!"
+                "Commands other than `track-up`, `track-down` and `list` are currently not supported!"
             ),
         }
     } else {
         let mut s = termion().unwrap();
-        let app = App::load_state();
+        let mut app = App::load_state(profile);
+        app.set_readonly(readonly);
+        let reminders = app.reminders().filter(|_| CONFIGURATION.reminders);
         let layout = NamedView::new(
             "Frame",
             LinearLayout::vertical().child(NamedView::new("Main", app)),
         );
         s.add_layer(layout);
-        s.add_global_callback(':', |s| open_command_window(s));
+        s.add_global_callback(keymap::key_for("command"), |s| open_command_window(s));
+        if let Some(text) = reminders {
+            open_report_window(&mut s, "Left for today", text);
+        }
 
         s.set_theme(theme::theme_gen());
+        // ticks on its own so that changes are saved even while a
+        // dialog is open on top of the board
+        let sink = s.cb_sink().clone();
+        thread::spawn(move || loop {
+            thread::sleep(Duration::from_secs(1));
+            let tick = sink.send(Box::new(|s: &mut Cursive| {
+                s.call_on_name("Main", |app: &mut App| app.autosave());
+            }));
+            if tick.is_err() {
+                break;
+            }
+        });
         s.run();
     }
 }
+
+// the saved habits for a single command, which would otherwise
+// run against an empty board when a save file does not load
+fn load_or_exit(profile: Option<String>) -> App {
+    let app = App::load_state(profile);
+    if let Some(e) = app.load_error() {
+        eprintln!("{}", e);
+        process::exit(1);
+    }
+    app
+}
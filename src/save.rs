@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::habit::HabitWrapper;
+
+// layout of the save files, bumped whenever older files need
+// more than serde defaults to load. version 0 is the bare list
+// of habits written before files carried a version
+pub const SAVE_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+struct SaveFile<'a, T: Serialize> {
+    version: u32,
+    habits: &'a [T],
+}
+
+#[derive(Deserialize)]
+struct Versioned {
+    version: u32,
+    habits: Value,
+}
+
+pub fn to_json<T: Serialize>(habits: &[T]) -> serde_json::Result<String> {
+    serde_json::to_string_pretty(&SaveFile {
+        version: SAVE_VERSION,
+        habits,
+    })
+}
+
+// habits from a save file of any version up to the current one
+pub fn from_json(j: &str) -> Result<Vec<Box<dyn HabitWrapper>>, String> {
+    let value: Value = serde_json::from_str(j).map_err(|e| e.to_string())?;
+    let (version, habits) = if value.is_array() {
+        (0, value)
+    } else {
+        let file: Versioned = serde_json::from_value(value).map_err(|e| e.to_string())?;
+        (file.version, file.habits)
+    };
+    if version > SAVE_VERSION {
+        return Err(format!("saved by a newer version, format {}", version));
+    }
+    let mut habits = serde_json::from_value(habits).map_err(|e| e.to_string())?;
+    migrate(version, &mut habits);
+    Ok(habits)
+}
+
+// brings habits read from a `version` file up to date, fields
+// that only gained a default, such as `step` or `goal_period`,
+// are already filled in by serde
+fn migrate(version: u32, habits: &mut Vec<Box<dyn HabitWrapper>>) {
+    if version < 1 {
+        // creation dates were not saved, the first entry is the
+        // best guess there is
+        for h in habits.iter_mut() {
+            let created = h.created();
+            h.set_created(created);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::habit::{Count, ViewMode};
+
+    #[test]
+    fn view_mode_and_offset_survive_a_save() {
+        let mut c: Box<dyn HabitWrapper> = Box::new(Count::new("water", 3, false));
+        c.set_view_mode(ViewMode::Year);
+        c.set_view_month_offset(2);
+        let habits = vec![c];
+        let loaded = from_json(&to_json(&habits).unwrap()).unwrap();
+        assert_eq!(loaded[0].view_mode(), ViewMode::Year);
+        assert_eq!(loaded[0].view_month_offset(), 2);
+    }
+
+    #[test]
+    fn bare_lists_and_versioned_files_both_load() {
+        let v0 = r#"[
+            {"type": "Count", "name": "water", "stats": {"2026-03-04": 2, "2026-03-02": 3}, "goal": 3},
+            {"type": "Bit", "name": "walk", "stats": {"2026-03-05": true}, "goal": true}
+        ]"#;
+        let habits = from_json(v0).unwrap();
+        assert_eq!(habits.len(), 2);
+        let date = |d| chrono::NaiveDate::from_ymd(2026, 3, d);
+        assert_eq!(habits[0].created(), date(2));
+        assert_eq!(habits[0].total(), 5);
+        assert!(habits[1].reached_goal(date(5)));
+
+        let v1 = to_json(&habits).unwrap();
+        assert!(v1.contains("\"version\": 1"));
+        let again = from_json(&v1).unwrap();
+        assert_eq!(again[0].name(), "water");
+        assert_eq!(again[0].created(), date(2));
+        assert_eq!(again[1].kind(), "Bit");
+
+        let v2 = r#"{"version": 2, "habits": []}"#;
+        assert!(from_json(v2).is_err());
+    }
+}
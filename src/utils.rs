@@ -1,14 +1,28 @@
+use chrono::format::{Item, StrftimeItems};
+use chrono::{Datelike, Local, NaiveDate, NaiveDateTime, Weekday};
 use cursive::theme::{BaseColor, Color};
 use directories::ProjectDirs;
+use lazy_static::lazy_static;
+use unicode_width::UnicodeWidthChar;
+
+use crate::command::SortKey;
+use crate::keymap::{self, Binding};
+use crate::CONFIGURATION;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::env;
 use std::fs;
 use std::path::PathBuf;
+use std::sync::Mutex;
 
 pub struct AppConfig {
     pub true_chr: char,
     pub false_chr: char,
     pub future_chr: char,
+    pub frozen_chr: char,
 
-    // view dimensions
+    // view dimensions, the width follows from the cells
+    pub cell_width: usize,
     pub view_width: usize,
     pub view_height: usize,
 
@@ -18,20 +32,484 @@ pub struct AppConfig {
     pub reached_color: Color,
     pub todo_color: Color,
     pub future_color: Color,
+    pub exceeded_color: Color,
+    pub today_color: Color,
+
+    // (fraction of goal, color) stops in ascending order,
+    // used to shade days that have not reached their goal
+    pub progress_gradient: Vec<(f64, Color)>,
+
+    // strftime patterns naming the month or year on display
+    pub month_format: String,
+    pub year_format: String,
+
+    // strftime pattern of every date shown to the user, saved
+    // files always hold iso dates
+    pub date_format: String,
+
+    // how entries are drawn in the day and recent views
+    pub cell_style: CellStyle,
+
+    // column that the month grid starts on
+    pub week_start: Weekday,
+
+    // seconds between saves of unsaved changes, 0 only
+    // saves on quitting
+    pub autosave_interval: u64,
+
+    // order the board is sorted in on every start, without
+    // one the order left by `:sort` is kept
+    pub sort_order: Option<SortKey>,
+
+    // ring the terminal bell once every habit is done for today
+    pub bell: bool,
+
+    // list what is left for today in a dialog on every start
+    pub reminders: bool,
+
+    // streak lengths that are pointed out once reached
+    pub milestones: Vec<u32>,
+
+    // hour of the night a new day starts at, entries made
+    // before it still count towards the previous day
+    pub day_rollover_hour: u32,
+
+    // actions moved off their own key
+    pub keybindings: Vec<Binding>,
+
+    // where save files go unless `--data-dir` says otherwise
+    pub data_dir: Option<PathBuf>,
+
+    // problems found in config.toml, shown once the app starts
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CellStyle {
+    // the entry itself
+    Number,
+    // how far the entry goes towards the goal
+    Bar,
+}
+
+// settings read from config.toml, anything left
+// out keeps its default
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct ConfigFile {
+    week_start: Option<String>,
+    autosave_interval: Option<u64>,
+    cell_width: Option<usize>,
+    cell_style: Option<String>,
+    bell: Option<bool>,
+    reminders: Option<bool>,
+    milestones: Option<Vec<u32>>,
+    day_rollover_hour: Option<u32>,
+    sort_order: Option<String>,
+    month_format: Option<String>,
+    year_format: Option<String>,
+    date_format: Option<String>,
+    color_complete: Option<String>,
+    color_incomplete: Option<String>,
+    color_today: Option<String>,
+    keybindings: BTreeMap<String, String>,
+    data_dir: Option<PathBuf>,
+    progress_gradient: Option<Vec<GradientStop>>,
+}
+
+// `progress_gradient = [{ at = 0.5, color = "yellow" }, ...]`
+#[derive(Deserialize)]
+struct GradientStop {
+    at: f64,
+    color: String,
+}
+
+// narrower cells cannot separate days, wider ones stop a
+// row of habits from fitting most terminals
+const CELL_WIDTH_RANGE: (usize, usize) = (2, 8);
+
+// settings from `text`, all of them left at their defaults
+// when it does not parse, with the reason why
+fn parse_config(text: &str) -> Result<ConfigFile, String> {
+    toml::from_str(text).map_err(|e| format!("Could not read config.toml, {}", e))
 }
 
 pub fn load_configuration_file() -> AppConfig {
+    let mut warnings = Vec::new();
+    // a missing file is no mistake, it only means defaults
+    let file = match fs::read_to_string(config_file()) {
+        Ok(text) => parse_config(&text).unwrap_or_else(|e| {
+            warnings.push(e);
+            ConfigFile::default()
+        }),
+        Err(_) => ConfigFile::default(),
+    };
+    let mut color = |key: &str, value: Option<String>, default: Color| match value {
+        Some(s) => parse_color(&s).unwrap_or_else(|| {
+            warnings.push(format!("Invalid color `{}` for {}", s, key));
+            default
+        }),
+        None => default,
+    };
+    let reached_color = color(
+        "color_complete",
+        file.color_complete,
+        Color::Dark(BaseColor::Cyan),
+    );
+    let todo_color = color(
+        "color_incomplete",
+        file.color_incomplete,
+        Color::Dark(BaseColor::Magenta),
+    );
+    let today_color = color(
+        "color_today",
+        file.color_today,
+        Color::Dark(BaseColor::Yellow),
+    );
+    let mut pattern = |key: &str, value: Option<String>, default: &str| match value {
+        Some(s) if StrftimeItems::new(&s).any(|i| i == Item::Error) => {
+            warnings.push(format!("Invalid {} `{}`", key, s));
+            default.to_owned()
+        }
+        Some(s) => s,
+        None => default.to_owned(),
+    };
+    let month_format = pattern("month_format", file.month_format, "%B %Y");
+    let year_format = pattern("year_format", file.year_format, "%Y");
+    let date_format = match file.date_format {
+        None => "%d/%b/%y".to_owned(),
+        // an unusable pattern is more likely a typo than a
+        // wish for the default, iso is at least unambiguous
+        Some(_) => pattern("date_format", file.date_format, ISO_DATE),
+    };
+    let sort_order = file.sort_order.and_then(|s| {
+        let key = SortKey::parse(&s);
+        if key.is_none() {
+            warnings.push(format!("Invalid sort_order `{}`", s));
+        }
+        key
+    });
+    let week_start = match file.week_start {
+        Some(s) => s.trim().parse().unwrap_or_else(|_| {
+            warnings.push(format!("Invalid week_start `{}`", s));
+            Weekday::Mon
+        }),
+        None => Weekday::Mon,
+    };
+    let day_rollover_hour = match file.day_rollover_hour {
+        Some(h) if h > 23 => {
+            warnings.push(format!("Invalid day_rollover_hour `{}`", h));
+            0
+        }
+        Some(h) => h,
+        None => 0,
+    };
+    let cell_style = match file.cell_style.as_deref().map(str::trim) {
+        None | Some("number") => CellStyle::Number,
+        Some("bar") => CellStyle::Bar,
+        Some(s) => {
+            warnings.push(format!("Invalid cell_style `{}`", s));
+            CellStyle::Number
+        }
+    };
+    let keybindings = keymap::bindings(file.keybindings, &mut warnings);
+    let progress_gradient = match file.progress_gradient {
+        Some(stops) => gradient(&stops).unwrap_or_else(|e| {
+            warnings.push(e);
+            default_gradient()
+        }),
+        None => default_gradient(),
+    };
+    let cell_width = file
+        .cell_width
+        .unwrap_or(3)
+        .max(CELL_WIDTH_RANGE.0)
+        .min(CELL_WIDTH_RANGE.1);
     return AppConfig {
         true_chr: '·',
         false_chr: '·',
         future_chr: '·',
-        view_width: 25,
+        frozen_chr: '~',
+        cell_width,
+        view_width: 7 * cell_width + 4,
         view_height: 8,
         grid_width: 3,
-        reached_color: Color::Dark(BaseColor::Cyan),
-        todo_color: Color::Dark(BaseColor::Magenta),
+        reached_color,
+        todo_color,
         future_color: Color::Light(BaseColor::Black),
+        exceeded_color: Color::Dark(BaseColor::Red),
+        today_color,
+        progress_gradient,
+        month_format,
+        year_format,
+        date_format,
+        cell_style,
+        week_start,
+        autosave_interval: file.autosave_interval.unwrap_or(30),
+        bell: file.bell.unwrap_or(true),
+        reminders: file.reminders.unwrap_or(false),
+        milestones: file.milestones.unwrap_or_else(|| vec![7, 30, 100, 365]),
+        day_rollover_hour,
+        sort_order,
+        keybindings,
+        // relative to the config file rather than wherever dijo
+        // happens to be started from
+        data_dir: file.data_dir.map(|d| config_file().with_file_name(d)),
+        warnings,
+    };
+}
+
+fn default_gradient() -> Vec<(f64, Color)> {
+    vec![
+        (0.0, Color::Dark(BaseColor::Magenta)),
+        (0.25, Color::Light(BaseColor::Magenta)),
+        (0.5, Color::Dark(BaseColor::Blue)),
+        (0.75, Color::Light(BaseColor::Blue)),
+    ]
+}
+
+// stops as written in config.toml, the whole gradient is
+// refused over a single bad one so half of it never shows
+fn gradient(stops: &[GradientStop]) -> Result<Vec<(f64, Color)>, String> {
+    let mut parsed = Vec::new();
+    for stop in stops {
+        if !stop.at.is_finite() || stop.at < 0. {
+            return Err(format!("Invalid progress_gradient stop `{}`", stop.at));
+        }
+        match parse_color(&stop.color) {
+            Some(c) => parsed.push((stop.at, c)),
+            None => {
+                return Err(format!(
+                    "Invalid color `{}` for progress_gradient",
+                    stop.color
+                ))
+            }
+        }
+    }
+    if parsed.windows(2).any(|w| w[0].0 >= w[1].0) {
+        return Err("progress_gradient stops are not in ascending order".to_owned());
+    }
+    Ok(parsed)
+}
+
+// color of the highest stop in `stops` that `ratio` has passed
+pub fn gradient_color(stops: &[(f64, Color)], ratio: f64) -> Option<Color> {
+    stops
+        .iter()
+        .rev()
+        .find(|(at, _)| ratio >= *at)
+        .map(|&(_, c)| c)
+}
+
+pub const ISO_DATE: &str = "%Y-%m-%d";
+
+pub fn format_date(date: NaiveDate, pattern: &str) -> String {
+    date.format(pattern).to_string()
+}
+
+// `date` the way the user asked for dates to be shown
+pub fn display_date(date: NaiveDate) -> String {
+    format_date(date, &CONFIGURATION.date_format)
+}
+
+lazy_static! {
+    static ref DATA_DIR_FLAG: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+    // stands in for the system clock when set, `DIJO_NOW` takes a
+    // local `yyyy-mm-ddThh:mm` so a run can be pinned to a moment
+    static ref CLOCK: Option<NaiveDateTime> = env::var("DIJO_NOW")
+        .ok()
+        .and_then(|s| NaiveDateTime::parse_from_str(&s, "%Y-%m-%dT%H:%M").ok());
+}
+
+// the day `now` belongs to when days start at `rollover_hour`
+pub fn day_of(now: NaiveDateTime, rollover_hour: u32) -> NaiveDate {
+    (now - chrono::Duration::hours(rollover_hour as i64)).date()
+}
+
+// the day entries made right now count towards
+pub fn today() -> NaiveDate {
+    let now = CLOCK.unwrap_or_else(|| Local::now().naive_local());
+    day_of(now, CONFIGURATION.day_rollover_hour)
+}
+
+// first day of the month that lies `offset` months before today
+pub fn view_month(offset: u32) -> NaiveDate {
+    let today = today();
+    let months = today.year() * 12 + today.month0() as i32 - offset as i32;
+    NaiveDate::from_ymd(months.div_euclid(12), months.rem_euclid(12) as u32 + 1, 1)
+}
+
+// number of months `date` lies before the current one, the
+// inverse of `view_month`
+pub fn month_offset(date: NaiveDate) -> u32 {
+    let today = today();
+    let months = |d: NaiveDate| d.year() * 12 + d.month0() as i32;
+    (months(today) - months(date)).max(0) as u32
+}
+
+// the `n` days that end on `last`, oldest first
+pub fn recent_days(last: NaiveDate, n: u32) -> Vec<NaiveDate> {
+    (0..n as i64)
+        .rev()
+        .map(|i| last - chrono::Duration::days(i))
+        .collect()
+}
+
+pub fn last_day_of_month(date: NaiveDate) -> NaiveDate {
+    let (y, m) = if date.month() == 12 {
+        (date.year() + 1, 1)
+    } else {
+        (date.year(), date.month() + 1)
+    };
+    NaiveDate::from_ymd(y, m, 1).pred()
+}
+
+// number of empty cells before the first of the month when
+// weeks begin on `week_start`
+pub fn leading_blanks(first: NaiveDate, week_start: Weekday) -> u32 {
+    (first.weekday().num_days_from_monday() + 7 - week_start.num_days_from_monday()) % 7
+}
+
+pub fn is_weekend(date: NaiveDate) -> bool {
+    matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+}
+
+// days of the month that `first` begins, each with the cell
+// it is drawn in, along with the number of cells in a row.
+// without weekends rows hold monday to friday and the
+// remaining days close up
+pub fn month_cells(
+    first: NaiveDate,
+    week_start: Weekday,
+    skip_weekends: bool,
+) -> (Vec<(NaiveDate, u32)>, u32) {
+    let days = (first.day()..=31)
+        .filter_map(|d| NaiveDate::from_ymd_opt(first.year(), first.month(), d))
+        .filter(|&d| !(skip_weekends && is_weekend(d)));
+    if !skip_weekends {
+        let lead = leading_blanks(first, week_start);
+        return (days.zip(lead..).collect(), 7);
+    }
+    let lead = if is_weekend(first) {
+        0
+    } else {
+        first.weekday().num_days_from_monday()
+    };
+    (days.zip(lead..).collect(), 5)
+}
+
+// cells, out of those laid out by `month_cells`, that close
+// an iso week in the middle of a row. weeks that end along
+// with their row need no marking
+pub fn week_breaks(cells: &[(NaiveDate, u32)], columns: u32) -> Vec<u32> {
+    cells
+        .windows(2)
+        .filter(|w| w[1].0.iso_week() != w[0].0.iso_week() && w[1].1 % columns != 0)
+        .map(|w| w[0].1)
+        .collect()
+}
+
+// centers `text` in `width` terminal columns, measured by
+// display width so that wide glyphs such as emoji line up,
+// anything wider than the cell is cut
+pub fn center(text: &str, width: usize) -> String {
+    let mut fitted = String::new();
+    let mut used = 0;
+    for c in text.trim().chars() {
+        let w = c.width().unwrap_or(0);
+        if used + w > width {
+            break;
+        }
+        fitted.push(c);
+        used += w;
+    }
+    let left = (width - used) / 2;
+    format!(
+        "{}{}{}",
+        " ".repeat(left),
+        fitted,
+        " ".repeat(width - used - left)
+    )
+}
+
+// an entry as it fits in `width` columns, numbers too long for
+// it are rounded down to thousands or millions and then capped,
+// so 12345 reads 12k and never a misleading 123. a capped number
+// below zero is cut off with an ellipsis, -9… rather than -99
+pub fn fit_value(text: &str, width: usize) -> String {
+    let text = text.trim();
+    if text.chars().count() <= width {
+        return text.to_owned();
+    }
+    let n = match text.parse::<i64>() {
+        Ok(n) => n,
+        Err(_) => return ellipsize(text, width),
     };
+    let short = [(1_000, "k"), (1_000_000, "M")]
+        .iter()
+        .filter(|&&(unit, _)| n / unit != 0)
+        .map(|&(unit, suffix)| format!("{}{}", n / unit, suffix))
+        .find(|s| s.chars().count() <= width);
+    match short {
+        Some(s) => s,
+        _ if width < 2 => if n < 0 { "-" } else { "+" }.to_owned(),
+        _ if n < 0 => format!("-{}…", "9".repeat(width - 2)),
+        _ => format!("{}+", "9".repeat(width - 1)),
+    }
+}
+
+// `text` cut down to `width` characters, ending on an ellipsis
+// when anything had to go
+pub fn ellipsize(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_owned();
+    }
+    match width {
+        0 => String::new(),
+        _ => text.chars().take(width - 1).chain(Some('…')).collect(),
+    }
+}
+
+// `ratio` of `width` filled in eighths of a block, anything
+// past the goal ends on a `+` instead of running over
+pub fn progress_bar(ratio: f64, width: usize) -> String {
+    const EIGHTHS: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+    if width == 0 {
+        return String::new();
+    }
+    if ratio > 1. {
+        return "█".repeat(width - 1) + "+";
+    }
+    let eighths = (ratio.max(0.) * (width * 8) as f64).round() as usize;
+    let mut bar = "█".repeat(eighths / 8);
+    if !eighths.is_multiple_of(8) {
+        bar.push(EIGHTHS[eighths % 8]);
+    }
+    format!("{:width$}", bar, width = width)
+}
+
+// one block per value, scaled so the largest value fills
+// its block, a run of zeros stays flat
+pub fn sparkline(values: &[u32]) -> String {
+    const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let max = values.iter().cloned().max().unwrap_or(0);
+    if max == 0 {
+        return BLOCKS[0].to_string().repeat(values.len());
+    }
+    values
+        .iter()
+        .map(|&v| BLOCKS[(v as u64 * 7 / max as u64) as usize])
+        .collect()
+}
+
+pub fn parse_color(s: &str) -> Option<Color> {
+    // cursive expects byte and char lengths of rgb
+    // shorthands to agree, keep it away from anything else
+    if !s.is_ascii() {
+        return None;
+    }
+    Color::parse(&s.to_lowercase())
 }
 
 fn project_dirs() -> ProjectDirs {
@@ -39,18 +517,283 @@ fn project_dirs() -> ProjectDirs {
         .unwrap_or_else(|| panic!("Invalid home directory!"))
 }
 
-pub fn habit_file() -> PathBuf {
+pub fn config_file() -> PathBuf {
     let proj_dirs = project_dirs();
-    let mut data_file = PathBuf::from(proj_dirs.data_dir());
-    fs::create_dir_all(&data_file);
-    data_file.push("habit_record.json");
+    let mut config_file = PathBuf::from(proj_dirs.config_dir());
+    config_file.push("config.toml");
+    config_file
+}
+
+// profiles become part of a file name, keep them to
+// characters that are safe in one
+pub fn valid_profile(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+// save file of `profile`, the default profile keeps the
+// original name
+pub fn profile_file_name(profile: Option<&str>, auto: bool) -> String {
+    let suffix = if auto { "[auto]" } else { "" };
+    match profile {
+        Some(p) => format!("habit_record_{}{}.json", p, suffix),
+        None => format!("habit_record{}.json", suffix),
+    }
+}
+
+// the directory every save file goes in, `--data-dir` wins
+// over `data_dir` in config.toml, which wins over the usual
+// place for the platform. it is made if it does not exist
+pub fn data_dir() -> PathBuf {
+    let flag = DATA_DIR_FLAG.lock().unwrap().clone();
+    let dir = resolve_data_dir(
+        flag,
+        CONFIGURATION.data_dir.clone(),
+        project_dirs().data_dir().to_path_buf(),
+    );
+    fs::create_dir_all(&dir);
+    dir
+}
+
+fn resolve_data_dir(flag: Option<PathBuf>, config: Option<PathBuf>, default: PathBuf) -> PathBuf {
+    flag.or(config).unwrap_or(default)
+}
+
+// set from `--data-dir` before anything is loaded, relative
+// paths are taken from where dijo was started
+pub fn set_data_dir(dir: &str) {
+    let dir = env::current_dir().map_or_else(|_| PathBuf::from(dir), |cwd| cwd.join(dir));
+    *DATA_DIR_FLAG.lock().unwrap() = Some(dir);
+}
+
+pub fn habit_file(profile: Option<&str>) -> PathBuf {
+    let mut data_file = data_dir();
+    data_file.push(profile_file_name(profile, false));
     return data_file;
 }
 
-pub fn auto_habit_file() -> PathBuf {
-    let proj_dirs = project_dirs();
-    let mut data_file = PathBuf::from(proj_dirs.data_dir());
-    fs::create_dir_all(&data_file);
-    data_file.push("habit_record[auto].json");
+// each profile keeps its own journal, so a replay never
+// touches habits of another profile that share a name
+pub fn journal_file(profile: Option<&str>) -> PathBuf {
+    let mut data_file = data_dir();
+    match profile {
+        Some(p) => data_file.push(format!("journal_{}.jsonl", p)),
+        None => data_file.push("journal.jsonl"),
+    }
+    data_file
+}
+
+pub fn auto_habit_file(profile: Option<&str>) -> PathBuf {
+    let mut data_file = data_dir();
+    data_file.push(profile_file_name(profile, true));
     return data_file;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leading_blanks_follow_the_week_start() {
+        // the first of october 2026 is a thursday
+        let first = NaiveDate::from_ymd(2026, 10, 1);
+        assert_eq!(leading_blanks(first, Weekday::Mon), 3);
+        assert_eq!(leading_blanks(first, Weekday::Sun), 4);
+        assert_eq!(leading_blanks(first, Weekday::Thu), 0);
+        assert_eq!("SUNDAY".parse::<Weekday>(), Ok(Weekday::Sun));
+    }
+
+    #[test]
+    fn config_syntax_errors_are_explained() {
+        assert!(parse_config("week_start = \"sunday\"").is_ok());
+        let typo = parse_config("week_start = sunday").err().unwrap();
+        assert!(typo.starts_with("Could not read config.toml, "));
+        assert!(typo.len() > "Could not read config.toml, ".len());
+        assert!(parse_config("week_start = 3").is_err());
+    }
+
+    #[test]
+    fn profile_paths() {
+        assert_eq!(profile_file_name(None, false), "habit_record.json");
+        assert_eq!(profile_file_name(None, true), "habit_record[auto].json");
+        assert_eq!(
+            profile_file_name(Some("work"), false),
+            "habit_record_work.json"
+        );
+        assert_eq!(
+            profile_file_name(Some("work"), true),
+            "habit_record_work[auto].json"
+        );
+        assert!(habit_file(Some("work")).ends_with("habit_record_work.json"));
+        assert!(journal_file(None).ends_with("journal.jsonl"));
+        assert!(journal_file(Some("work")).ends_with("journal_work.jsonl"));
+    }
+
+    #[test]
+    fn progress_falls_in_the_highest_passed_bucket() {
+        let stops = default_gradient();
+        let color = |r| gradient_color(&stops, r);
+        assert_eq!(color(0.1), Some(Color::Dark(BaseColor::Magenta)));
+        assert_eq!(color(0.25), Some(Color::Light(BaseColor::Magenta)));
+        assert_eq!(color(0.6), Some(Color::Dark(BaseColor::Blue)));
+        assert_eq!(color(1.0), Some(Color::Light(BaseColor::Blue)));
+        assert_eq!(color(3.0), Some(Color::Light(BaseColor::Blue)));
+    }
+
+    #[test]
+    fn sparkline_scales_to_the_largest_value() {
+        assert_eq!(sparkline(&[0, 1, 2, 4, 8]), "▁▁▂▄█");
+        assert_eq!(sparkline(&[3, 3]), "██");
+        assert_eq!(sparkline(&[0, 0, 0]), "▁▁▁");
+        assert_eq!(sparkline(&[]), "");
+    }
+
+    #[test]
+    fn centered_cells_share_a_display_width() {
+        use unicode_width::UnicodeWidthStr;
+        for glyph in &["x", "·", "✅", "🔥", "👩‍💻", "12345"] {
+            assert_eq!(center(glyph, 3).width(), 3, "{}", glyph);
+        }
+        assert_eq!(center("x", 3), " x ");
+        assert_eq!(center("🔥", 3), "🔥 ");
+    }
+
+    #[test]
+    fn weekends_close_up_in_the_month_cells() {
+        let first = NaiveDate::from_ymd(2026, 2, 1);
+        let fortnight = |cells: Vec<(NaiveDate, u32)>| {
+            cells
+                .into_iter()
+                .filter(|(d, _)| d.day() <= 14)
+                .collect::<Vec<_>>()
+        };
+        let (all, columns) = month_cells(first, Weekday::Mon, false);
+        assert_eq!(columns, 7);
+        assert_eq!(fortnight(all).len(), 14);
+
+        let (work, columns) = month_cells(first, Weekday::Mon, true);
+        assert_eq!(columns, 5);
+        let work = fortnight(work);
+        assert_eq!(work.len(), 10);
+        assert_eq!(work[0], (NaiveDate::from_ymd(2026, 2, 2), 0));
+        assert_eq!(work[9], (NaiveDate::from_ymd(2026, 2, 13), 9));
+    }
+
+    #[test]
+    fn dates_follow_the_given_pattern() {
+        let d = NaiveDate::from_ymd(2026, 3, 7);
+        assert_eq!(format_date(d, "%d/%m/%Y"), "07/03/2026");
+        assert_eq!(format_date(d, "%d/%b/%y"), "07/Mar/26");
+        assert_eq!(format_date(d, ISO_DATE), "2026-03-07");
+    }
+
+    #[test]
+    fn recent_days_end_on_the_last_day() {
+        let last = NaiveDate::from_ymd(2026, 3, 5);
+        let days = recent_days(last, 14);
+        assert_eq!(days.len(), 14);
+        assert_eq!(days[0], NaiveDate::from_ymd(2026, 2, 20));
+        assert_eq!(days[13], last);
+        assert!(days.windows(2).all(|w| w[1] == w[0].succ()));
+    }
+
+    #[test]
+    fn week_breaks_only_inside_a_row() {
+        let first = NaiveDate::from_ymd(2026, 3, 1);
+        let (cells, columns) = month_cells(first, Weekday::Sun, false);
+        assert_eq!(week_breaks(&cells, columns), vec![0, 7, 14, 21, 28]);
+        let (cells, columns) = month_cells(first, Weekday::Mon, false);
+        assert!(week_breaks(&cells, columns).is_empty());
+    }
+
+    #[test]
+    fn rollover_hour_moves_the_start_of_the_day() {
+        let at = |d, h, m| NaiveDate::from_ymd(2026, 3, d).and_hms(h, m, 0);
+        let day = |d| NaiveDate::from_ymd(2026, 3, d);
+        assert_eq!(day_of(at(5, 23, 59), 0), day(5));
+        assert_eq!(day_of(at(6, 0, 0), 0), day(6));
+        assert_eq!(day_of(at(6, 0, 30), 4), day(5));
+        assert_eq!(day_of(at(6, 3, 59), 4), day(5));
+        assert_eq!(day_of(at(6, 4, 0), 4), day(6));
+        assert_eq!(day_of(at(1, 2, 0), 4), NaiveDate::from_ymd(2026, 2, 28));
+    }
+
+    #[test]
+    fn progress_bar_fills_in_eighths() {
+        assert_eq!(progress_bar(0., 3), "   ");
+        assert_eq!(progress_bar(1. / 24., 3), "▏  ");
+        assert_eq!(progress_bar(0.5, 3), "█▌ ");
+        assert_eq!(progress_bar(1., 3), "███");
+        assert_eq!(progress_bar(1.5, 3), "██+");
+        assert_eq!(progress_bar(0.5, 0), "");
+    }
+
+    #[test]
+    fn data_dir_flag_then_config_then_default() {
+        let (flag, config, default) = (
+            PathBuf::from("/flag"),
+            PathBuf::from("/config"),
+            PathBuf::from("/default"),
+        );
+        assert_eq!(
+            resolve_data_dir(Some(flag.clone()), Some(config.clone()), default.clone()),
+            flag
+        );
+        assert_eq!(
+            resolve_data_dir(None, Some(config.clone()), default.clone()),
+            config
+        );
+        assert_eq!(resolve_data_dir(None, None, default.clone()), default);
+    }
+
+    #[test]
+    fn values_shorten_to_fit_their_cell() {
+        assert_eq!(center(&fit_value("7", 3), 3), " 7 ");
+        assert_eq!(center(&fit_value("12345", 3), 3), "12k");
+        assert_eq!(fit_value("1234567", 3), "1M");
+        assert_eq!(fit_value("123456", 3), "99+");
+        assert_eq!(fit_value("-1234", 3), "-1k");
+        assert_eq!(fit_value("-12345", 3), "-9…");
+        assert_eq!(fit_value("12345", 1), "+");
+        assert_eq!(fit_value("1:30h", 3), "1:…");
+    }
+
+    #[test]
+    fn capped_negative_values_are_marked_as_cut() {
+        assert_eq!(fit_value("-123456", 4), "-99…");
+        assert_eq!(fit_value("-123456", 3), "-9…");
+        assert_eq!(fit_value("-12345", 2), "-…");
+        assert_eq!(fit_value("-12345", 1), "-");
+        assert_eq!(fit_value("-99", 3), "-99");
+    }
+
+    #[test]
+    fn gradient_from_a_sample_config() {
+        let sample = r##"
+            progress_gradient = [
+                { at = 0.0, color = "red" },
+                { at = 0.5, color = "yellow" },
+                { at = 1.0, color = "#00ff00" },
+            ]
+        "##;
+        let file: ConfigFile = toml::from_str(sample).unwrap();
+        let stops = gradient(&file.progress_gradient.unwrap()).unwrap();
+        let color = |r| gradient_color(&stops, r);
+        assert_eq!(color(0.2), Some(Color::Dark(BaseColor::Red)));
+        assert_eq!(color(0.5), Some(Color::Dark(BaseColor::Yellow)));
+        assert_eq!(color(0.99), Some(Color::Dark(BaseColor::Yellow)));
+        assert_eq!(color(1.0), Some(Color::Rgb(0, 255, 0)));
+        assert_eq!(color(1.7), Some(Color::Rgb(0, 255, 0)));
+
+        let unsorted: ConfigFile = toml::from_str(
+            r#"progress_gradient = [{ at = 0.5, color = "red" }, { at = 0.2, color = "blue" }]"#,
+        )
+        .unwrap();
+        assert!(gradient(&unsorted.progress_gradient.unwrap()).is_err());
+        let bad_color: ConfigFile =
+            toml::from_str(r#"progress_gradient = [{ at = 0.5, color = "reddish" }]"#).unwrap();
+        assert!(gradient(&bad_color.progress_gradient.unwrap()).is_err());
+    }
+}
@@ -1,14 +1,21 @@
 use cursive::direction::Direction;
 use cursive::event::{Event, EventResult, Key};
 use cursive::theme::{Effect, Style};
-use cursive::view::View;
-use cursive::{Printer, Vec2};
+use cursive::utils::markup::StyledString;
+use cursive::view::{Nameable, Resizable, Scrollable, View};
+use cursive::views::{Dialog, EditView, LinearLayout, OnEventView, TextView};
+use cursive::{Cursive, Printer, Vec2};
 
 use chrono::prelude::*;
-use chrono::{Duration, Local, NaiveDate};
+use chrono::NaiveDate;
 
-use crate::habit::{Bit, Count, Habit, TrackEvent, ViewMode};
+use crate::habit::{
+    Bit, Capped, Checklist, Count, Duration, FloatCount, GoalPeriod, Habit, HabitWrapper,
+    TrackEvent, ViewMode,
+};
 
+use crate::app::App;
+use crate::utils::{self, CellStyle};
 use crate::CONFIGURATION;
 
 pub trait ShadowView {
@@ -26,24 +33,25 @@ where
     T::HabitType: std::fmt::Display,
 {
     fn draw(&self, printer: &Printer) {
-        let now = if self.view_month_offset() == 0 {
-            Local::today()
-        } else {
-            Local::today()
-                .checked_sub_signed(Duration::weeks(4 * self.view_month_offset() as i64))
-                .unwrap()
-        };
+        let now = utils::view_month(self.view_month_offset());
         let year = now.year();
         let month = now.month();
 
-        let goal_reached_style = Style::from(CONFIGURATION.reached_color);
+        let goal_reached_style = Style::from(self.color().unwrap_or(CONFIGURATION.reached_color));
         let todo_style = Style::from(CONFIGURATION.todo_color);
         let future_style = Style::from(CONFIGURATION.future_color);
+        let exceeded_style = Style::from(CONFIGURATION.exceeded_color);
+        let today_style = Style::from(CONFIGURATION.today_color);
 
         let strikethrough = Style::from(Effect::Strikethrough);
 
-        let goal_status =
-            self.view_month_offset() == 0 && self.reached_goal(Local::now().naive_local().date());
+        // the name turns to the warning color while a streak
+        // waits on today
+        let at_risk = self.streak_at_risk(utils::today());
+
+        let goal_status = self.view_month_offset() == 0
+            && !self.is_log_only()
+            && self.reached_goal(utils::today());
 
         printer.with_style(
             Style::merge(&[
@@ -52,37 +60,100 @@ where
                 } else {
                     Style::none()
                 },
-                if !printer.focused {
+                if !printer.focused || self.is_paused(utils::today()) {
                     future_style
                 } else {
                     Style::none()
                 },
+                if at_risk { today_style } else { Style::none() },
             ]),
             |p| {
+                let width = (CONFIGURATION.view_width - 13).min(p.size.x.saturating_sub(2));
                 p.print(
                     (0, 0),
-                    &format!(
-                        " {:.width$} ",
-                        self.name(),
-                        width = CONFIGURATION.view_width - 6
-                    ),
+                    &format!(" {} ", utils::ellipsize(&self.name(), width)),
                 );
             },
         );
 
+        // whether today is done, wherever the grid is scrolled to
+        let today = utils::today();
+        let done = if self.is_log_only() || !self.counts_towards_goal(today) {
+            None
+        } else {
+            Some(self.reached_goal(today))
+        };
+        let meta = self.meta();
+        let mark_style = match done {
+            Some(true) => goal_reached_style,
+            Some(false) => todo_style,
+            None => future_style,
+        };
+        printer.with_style(mark_style, |p| {
+            p.print(
+                (CONFIGURATION.view_width - 11, 0),
+                &today_mark(done, meta.true_chr, meta.false_chr).to_string(),
+            );
+        });
+
+        // completion over the days on display, up to today
+        let (from, to) = match self.view_mode() {
+            ViewMode::Year => (
+                NaiveDate::from_ymd(year, 1, 1),
+                NaiveDate::from_ymd(year, 12, 31),
+            ),
+            ViewMode::Recent(n) => (
+                self.cursor() - chrono::Duration::days(n as i64 - 1),
+                self.cursor(),
+            ),
+            _ => (now, utils::last_day_of_month(now)),
+        };
+        let rate = if self.is_log_only() {
+            None
+        } else {
+            self.checked_completion_rate(from, to.min(today))
+        };
+        printer.with_style(future_style, |p| {
+            p.print(
+                (CONFIGURATION.view_width - 9, 0),
+                &format!("{:>4}", percent_label(rate)),
+            );
+        });
+
+        let streak = self.get_streak(utils::today());
+        if streak > 0 && !self.is_log_only() {
+            printer.with_style(future_style, |p| {
+                p.print(
+                    (CONFIGURATION.view_width - 4, 0),
+                    &format!("{:>3}", format!("{}d", streak)),
+                );
+            });
+        }
+
+        // pace towards a target total, drawn between the name
+        // and the streak
+        if let Some(on_track) = self.on_track(utils::today()) {
+            let (style, marker) = if on_track {
+                (goal_reached_style, "▲")
+            } else {
+                (todo_style, "▼")
+            };
+            printer.with_style(style, |p| {
+                p.print((CONFIGURATION.view_width - 5, 0), marker);
+            });
+        }
+
         let draw_week = |printer: &Printer| {
             let days = (1..31)
                 .map(|i| NaiveDate::from_ymd_opt(year, month, i))
                 .flatten() // dates 28-31 may not exist, ignore them if they don't
                 .collect::<Vec<_>>();
             for (week, line_nr) in days.chunks(7).zip(2..) {
-                let weekly_goal = self.goal() * week.len() as u32;
-                let is_this_week = week.contains(&Local::now().naive_local().date());
-                let remaining = week.iter().map(|&i| self.remaining(i)).sum::<u32>();
-                let completions = weekly_goal - remaining;
+                let (completions, weekly_goal) = week_completions(self, week);
+                let is_this_week = week.contains(&utils::today());
                 let full = CONFIGURATION.view_width - 8;
                 let bars_to_fill = if weekly_goal > 0 {
-                    (completions * full as u32) / weekly_goal
+                    completions * full as u64 / weekly_goal
                 } else {
                     0
                 };
@@ -110,38 +181,166 @@ where
             }
         };
 
+        let day_style = |d: NaiveDate| {
+            if self.is_log_only() {
+                Style::none()
+            } else if self.reached_goal(d) {
+                goal_reached_style
+            } else if !self.is_scheduled(d) {
+                future_style
+            } else if self.over_limit(d) {
+                exceeded_style
+            } else if let Some(ratio) = self.progress(d) {
+                progress_style(ratio).unwrap_or(todo_style)
+            } else {
+                todo_style
+            }
+        };
+
+        // the cursor is only drawn once it leaves today
+        let is_cursor = |d: NaiveDate| d == self.cursor() && d != utils::today();
+
+        // a single day of the day or recent view, `coords` is the
+        // top left corner of its cell
+        let draw_cell = |printer: &Printer, d: NaiveDate, coords: Vec2| {
+            let w = CONFIGURATION.cell_width;
+            let created = self.created();
+            let today = utils::today();
+            // today stands out whether or not it is done
+            let (day_style, empty_style) = if d == today {
+                (today_style, today_style)
+            } else {
+                (day_style(d), future_style)
+            };
+            let note_style = if self.get_note(d).is_some() {
+                Style::from(Effect::Underline)
+            } else {
+                Style::none()
+            };
+            let found_style = if self.is_highlighted(d) || (printer.focused && is_cursor(d)) {
+                Style::from(Effect::Reverse)
+            } else {
+                Style::none()
+            };
+            if d < created {
+                // the habit did not exist yet, nothing was missed
+            } else if self.is_resting(d) {
+                printer.with_style(Style::merge(&[future_style, note_style]), |p| {
+                    p.print(
+                        coords,
+                        &utils::center(&CONFIGURATION.frozen_chr.to_string(), w),
+                    );
+                });
+            } else if let Some(c) = self.get_by_date(d) {
+                // values wider than the cell are cut rather
+                // than spilling into the next day, the width
+                // lets values pick a shorter form
+                let bar = self
+                    .fill(d)
+                    .filter(|&r| r > 0. && CONFIGURATION.cell_style == CellStyle::Bar);
+                let text = match (self.glyph(d), bar) {
+                    (Some(g), _) => utils::center(&g.to_string(), w),
+                    // the last column keeps days apart
+                    (None, Some(r)) => utils::progress_bar(r, w - 1) + " ",
+                    (None, None) => utils::center(&utils::fit_value(&c.to_string(), w), w),
+                };
+                printer.with_style(Style::merge(&[day_style, note_style, found_style]), |p| {
+                    p.print(coords, &text);
+                });
+            } else {
+                printer.with_style(Style::merge(&[empty_style, note_style, found_style]), |p| {
+                    p.print(
+                        coords,
+                        &utils::center(&CONFIGURATION.future_chr.to_string(), w),
+                    );
+                });
+            }
+        };
+
         let draw_day = |printer: &Printer| {
-            let mut i = 0;
-            while let Some(d) = NaiveDate::from_ymd_opt(year, month, i + 1) {
-                let day_style;
-                if self.reached_goal(d) {
-                    day_style = goal_reached_style;
-                } else {
-                    day_style = todo_style;
+            let (cells, columns) =
+                utils::month_cells(now, CONFIGURATION.week_start, self.skips_weekends());
+            let w = CONFIGURATION.cell_width;
+            let at = |cell: u32| -> Vec2 {
+                ((cell % columns) as usize * w, (cell / columns + 2) as usize).into()
+            };
+            for &(d, cell) in &cells {
+                draw_cell(printer, d, at(cell));
+            }
+            // weekly goals start over on mondays, which only
+            // needs pointing out when rows begin on another day.
+            // the mark takes the last column of the closing day,
+            // which centered values leave blank
+            if self.goal_period() == GoalPeriod::Weekly {
+                for cell in utils::week_breaks(&cells, columns) {
+                    printer.with_style(future_style, |p| {
+                        p.print(at(cell) + (w - 1, 0), "│");
+                    });
                 }
-                let coords: Vec2 = ((i % 7) * 3, i / 7 + 2).into();
-                if let Some(c) = self.get_by_date(d) {
-                    printer.with_style(day_style, |p| {
-                        p.print(coords, &format!("{:^3}", c));
+            }
+        };
+
+        // the last `n` days up to the cursor in a single row,
+        // the most recent on the right
+        let draw_recent = |printer: &Printer, n: u32| {
+            let w = CONFIGURATION.cell_width;
+            for (d, i) in utils::recent_days(self.cursor(), n).into_iter().zip(0..) {
+                draw_cell(printer, d, (i * w, 2).into());
+            }
+        };
+
+        // one column per week and one row per weekday, in the
+        // manner of a contribution graph
+        let draw_year = |printer: &Printer| {
+            let first = NaiveDate::from_ymd(year, 1, 1);
+            let lead = utils::leading_blanks(first, CONFIGURATION.week_start);
+            let created = self.created();
+            let label = format!("{} · total {}", year, self.total());
+            let x = YEAR_WIDTH.saturating_sub(label.chars().count());
+            printer.with_style(future_style, |p| p.print((x, 0), &label));
+            let mut i = 0;
+            while let Some(d) = first.with_ordinal(i + 1) {
+                let cell = i + lead;
+                let coords: Vec2 = (cell / 7, cell % 7 + 1).into();
+                if d < created {
+                    // blank until the habit existed
+                } else if self.is_resting(d) {
+                    printer.with_style(future_style, |p| {
+                        p.print(coords, &CONFIGURATION.frozen_chr.to_string())
                     });
+                } else if self.get_by_date(d).is_some() {
+                    printer.with_style(day_style(d), |p| p.print(coords, "■"));
                 } else {
                     printer.with_style(future_style, |p| {
-                        p.print(coords, &format!("{:^3}", CONFIGURATION.future_chr));
+                        p.print(coords, &CONFIGURATION.future_chr.to_string())
                     });
                 }
                 i += 1;
             }
         };
 
+        // short of room for its view, a habit keeps to the most
+        // recent days that still fit
+        if printer.size.x < view_size(self.view_mode()).x {
+            let fit = printer.size.x / CONFIGURATION.cell_width;
+            if fit > 0 {
+                draw_recent(printer, fit as u32);
+            }
+            return;
+        }
+
         match self.view_mode() {
             ViewMode::Day => draw_day(printer),
             ViewMode::Week => draw_week(printer),
+            ViewMode::Year => draw_year(printer),
+            ViewMode::Recent(n) => draw_recent(printer, n),
             _ => draw_day(printer),
         };
     }
 
-    fn required_size(&mut self, _: Vec2) -> Vec2 {
-        (25, 6).into()
+    fn required_size(&mut self, constraint: Vec2) -> Vec2 {
+        let size = view_size(self.view_mode());
+        Vec2::new(size.x.min(constraint.x), size.y.min(constraint.y))
     }
 
     fn take_focus(&mut self, _: Direction) -> bool {
@@ -149,7 +348,7 @@ where
     }
 
     fn on_event(&mut self, e: Event) -> EventResult {
-        let now = Local::now().naive_local().date();
+        let now = self.cursor();
         if self.is_auto() {
             return EventResult::Ignored;
         }
@@ -162,11 +361,74 @@ where
                 self.modify(now, TrackEvent::Decrement);
                 return EventResult::Consumed(None);
             }
+            Event::Char('x') => {
+                self.clear_entry(now);
+                EventResult::Consumed(None)
+            }
+            Event::Char('f') => {
+                self.toggle_freeze(now);
+                EventResult::Consumed(None)
+            }
             _ => return EventResult::Ignored,
         }
     }
 }
 
+// space taken up on the grid by a habit drawn in `mode`,
+// a year needs room for every week side by side
+pub fn view_size(mode: ViewMode) -> Vec2 {
+    match mode {
+        ViewMode::Year => (YEAR_WIDTH, CONFIGURATION.view_height).into(),
+        ViewMode::Recent(n) => (
+            (n as usize * CONFIGURATION.cell_width).max(CONFIGURATION.view_width),
+            CONFIGURATION.view_height,
+        )
+            .into(),
+        _ => (CONFIGURATION.view_width, CONFIGURATION.view_height).into(),
+    }
+}
+
+// weeks touched by a leap year that starts on the last day
+// of the week
+const YEAR_WIDTH: usize = 54;
+
+// amount done and goal over the days of `week`. a day below
+// zero has more than its goal left, but cannot take away from
+// what the other days did
+fn week_completions<T: Habit>(habit: &T, week: &[NaiveDate]) -> (u64, u64) {
+    let goal = habit.goal() as u64;
+    let remaining = week
+        .iter()
+        .map(|&d| (habit.remaining(d) as u64).min(goal))
+        .sum::<u64>();
+    let weekly_goal = goal * week.len() as u64;
+    (weekly_goal - remaining, weekly_goal)
+}
+
+// done or not done today, in the habit's own glyphs if it has
+// them. a dash when today does not count
+fn today_mark(done: Option<bool>, true_chr: Option<char>, false_chr: Option<char>) -> char {
+    match done {
+        Some(true) => true_chr.unwrap_or('✓'),
+        Some(false) => false_chr.unwrap_or('✗'),
+        None => '–',
+    }
+}
+
+// whole percent of a completion rate, a dash when there
+// was nothing to complete
+fn percent_label(rate: Option<f64>) -> String {
+    match rate {
+        Some(r) => format!("{}%", (r * 100.).round() as u32),
+        None => "—".to_owned(),
+    }
+}
+
+// color of the highest gradient stop that `ratio` has passed
+fn progress_style(ratio: f64) -> Option<Style> {
+    utils::gradient_color(&CONFIGURATION.progress_gradient, ratio).map(Style::from)
+}
+
 macro_rules! auto_view_impl {
     ($struct_name:ident) => {
         impl View for $struct_name {
@@ -188,3 +450,405 @@ macro_rules! auto_view_impl {
 
 auto_view_impl!(Count);
 auto_view_impl!(Bit);
+auto_view_impl!(FloatCount);
+auto_view_impl!(Duration);
+auto_view_impl!(Capped);
+auto_view_impl!(Checklist);
+
+#[derive(Clone)]
+pub struct StatsRow {
+    pub name: String,
+    pub total: u32,
+    pub completed: u32,
+    pub rate: f64,
+    pub streak: u32,
+    // amounts of the last days up to the end of the month
+    pub trend: String,
+}
+
+// summary of every habit for one month, `[` and `]` move
+// through months just like the main grid does
+pub struct StatsView {
+    offset: u32,
+    rows: Vec<StatsRow>,
+}
+
+impl StatsView {
+    pub fn new(offset: u32, rows: Vec<StatsRow>) -> Self {
+        StatsView { offset, rows }
+    }
+
+    fn show_month(&self, offset: u32) -> EventResult {
+        EventResult::with_cb(move |s| {
+            if let Some(rows) = s.call_on_name("Main", |app: &mut App| app.month_stats(offset)) {
+                s.call_on_name("Stats", |v: &mut StatsView| {
+                    v.offset = offset;
+                    v.rows = rows;
+                });
+            }
+        })
+    }
+}
+
+impl View for StatsView {
+    fn draw(&self, printer: &Printer) {
+        let month = utils::view_month(self.offset);
+        printer.with_style(Effect::Bold, |p| {
+            p.print(
+                (0, 0),
+                &format!("{}", month.format(&CONFIGURATION.month_format)),
+            );
+        });
+        printer.with_style(Style::from(CONFIGURATION.future_color), |p| {
+            p.print(
+                (0, 2),
+                &format!(
+                    "{:20} {:>6} {:>5} {:>5} {:>6} {}",
+                    "habit", "total", "days", "rate", "streak", "last 30 days"
+                ),
+            );
+        });
+        for (row, line_nr) in self.rows.iter().zip(3..) {
+            printer.print(
+                (0, line_nr),
+                &format!(
+                    "{:20.20} {:>6} {:>5} {:>4.0}% {:>6} {}",
+                    row.name,
+                    row.total,
+                    row.completed,
+                    row.rate * 100.,
+                    row.streak,
+                    row.trend
+                ),
+            );
+        }
+    }
+
+    fn required_size(&mut self, _: Vec2) -> Vec2 {
+        (77, self.rows.len() + 3).into()
+    }
+
+    fn take_focus(&mut self, _: Direction) -> bool {
+        true
+    }
+
+    fn on_event(&mut self, e: Event) -> EventResult {
+        match e {
+            Event::Char('[') => self.show_month(self.offset + 1),
+            Event::Char(']') if self.offset > 0 => self.show_month(self.offset - 1),
+            Event::Char('q') | Event::Char('S') | Event::Key(Key::Esc) => {
+                EventResult::with_cb(|s| {
+                    s.pop_layer();
+                })
+            }
+            _ => EventResult::Ignored,
+        }
+    }
+}
+
+// notes shown at the bottom of the detail view
+const DETAIL_NOTES: usize = 5;
+
+// one habit over the current year along with its numbers and
+// latest notes. it draws a copy taken when it was opened, so
+// it reflects any edit once opened again
+pub struct DetailView {
+    habit: Box<dyn HabitWrapper>,
+    lines: Vec<String>,
+    notes: Vec<String>,
+}
+
+impl DetailView {
+    pub fn new(mut habit: Box<dyn HabitWrapper>) -> Self {
+        habit.set_view_mode(ViewMode::Year);
+        habit.set_view_month_offset(0);
+        let today = utils::today();
+        let year_start = NaiveDate::from_ymd(today.year(), 1, 1);
+        let dates = habit.tracked_dates();
+        let range = match (dates.iter().min(), dates.iter().max()) {
+            (Some(&f), Some(&l)) => {
+                format!("{} to {}", utils::display_date(f), utils::display_date(l))
+            }
+            _ => "no data".to_owned(),
+        };
+        let lines = vec![
+            format!(
+                "streak {}, longest {}",
+                habit.get_streak(today),
+                habit.longest_streak()
+            ),
+            format!(
+                "total {} over {} days, {}",
+                habit.total(),
+                dates.len(),
+                range
+            ),
+            format!(
+                "done {:.0}% this month, {:.0}% this year, {:.0}% overall",
+                habit.completion_rate(utils::view_month(0), today) * 100.,
+                habit.completion_rate(year_start, today) * 100.,
+                habit.completion_rate(habit.created(), today) * 100.
+            ),
+        ];
+        let mut noted = habit.meta().notes.iter().collect::<Vec<_>>();
+        noted.sort_by(|a, b| b.0.cmp(a.0));
+        let notes = noted
+            .into_iter()
+            .take(DETAIL_NOTES)
+            .map(|(d, n)| format!("{}  {}", utils::display_date(*d), n))
+            .collect();
+        DetailView {
+            habit,
+            lines,
+            notes,
+        }
+    }
+}
+
+impl View for DetailView {
+    fn draw(&self, printer: &Printer) {
+        self.habit.draw(&printer.focused(true));
+        let mut line_nr = CONFIGURATION.view_height + 1;
+        for line in &self.lines {
+            printer.print((1, line_nr), line);
+            line_nr += 1;
+        }
+        line_nr += 1;
+        let notes_style = Style::from(CONFIGURATION.future_color);
+        if self.notes.is_empty() {
+            printer.with_style(notes_style, |p| p.print((1, line_nr), "no notes"));
+        }
+        for note in &self.notes {
+            printer.with_style(notes_style, |p| p.print((1, line_nr), note));
+            line_nr += 1;
+        }
+    }
+
+    fn required_size(&mut self, _: Vec2) -> Vec2 {
+        let width = self
+            .lines
+            .iter()
+            .chain(&self.notes)
+            .map(|l| l.chars().count() + 1)
+            .fold(YEAR_WIDTH, usize::max);
+        let height = CONFIGURATION.view_height + self.lines.len() + self.notes.len().max(1) + 2;
+        (width, height).into()
+    }
+
+    fn take_focus(&mut self, _: Direction) -> bool {
+        true
+    }
+
+    fn on_event(&mut self, e: Event) -> EventResult {
+        match e {
+            Event::Char('q') | Event::Key(Key::Esc) => EventResult::with_cb(|s| {
+                s.pop_layer();
+            }),
+            _ => EventResult::Ignored,
+        }
+    }
+}
+
+// `habit` as serialized by the board, taken apart again into a
+// copy the detail view can keep
+pub fn open_detail_window(s: &mut Cursive, habit: serde_json::Value) {
+    if let Ok(habit) = serde_json::from_value::<Box<dyn HabitWrapper>>(habit) {
+        let title = habit.name();
+        s.add_fullscreen_layer(Dialog::around(DetailView::new(habit)).title(title));
+    }
+}
+
+pub fn open_stats_window(s: &mut Cursive, offset: u32, rows: Vec<StatsRow>) {
+    s.add_layer(
+        Dialog::around(StatsView::new(offset, rows).with_name("Stats")).title("Statistics"),
+    );
+}
+
+// dialog to edit the note of `habit` on `date`, an empty
+// note removes it
+pub fn open_note_window(s: &mut Cursive, habit: String, date: NaiveDate, note: String) {
+    let save = move |s: &mut Cursive, text: &str| {
+        s.call_on_name("Main", |app: &mut App| app.set_note(&habit, date, text));
+        s.pop_layer();
+    };
+    let submit = save.clone();
+    s.add_layer(
+        Dialog::new()
+            .title(format!("Note for {}", utils::display_date(date)))
+            .content(
+                EditView::new()
+                    .content(note)
+                    .on_submit(save)
+                    .with_name("Note")
+                    .fixed_width(CONFIGURATION.view_width),
+            )
+            .button("Ok", move |s| {
+                let text = s
+                    .call_on_name("Note", |v: &mut EditView| v.get_content())
+                    .unwrap();
+                submit(s, &text);
+            })
+            .dismiss_button("Cancel"),
+    );
+}
+
+// read-only text, such as a report, that may be taller
+// than the terminal
+pub fn open_report_window(s: &mut Cursive, title: &str, text: String) {
+    let dialog = Dialog::around(TextView::new(text).scrollable())
+        .title(title)
+        .dismiss_button("Close");
+    s.add_layer(
+        OnEventView::new(dialog)
+            .on_event('q', |s| {
+                s.pop_layer();
+            })
+            .on_event(Key::Esc, |s| {
+                s.pop_layer();
+            }),
+    );
+}
+
+// dialog to type the exact entry of `habit` on `date`, it
+// stays open until the value is accepted
+pub fn open_value_window(s: &mut Cursive, habit: String, date: NaiveDate, value: String) {
+    let save = move |s: &mut Cursive, text: &str| {
+        let result = s
+            .call_on_name("Main", |app: &mut App| app.set_entry(&habit, date, text))
+            .unwrap_or(Ok(()));
+        match result {
+            Ok(_) => {
+                s.pop_layer();
+            }
+            Err(e) => {
+                s.call_on_name("ValueError", |v: &mut TextView| {
+                    v.set_content(StyledString::styled(e, CONFIGURATION.exceeded_color))
+                });
+            }
+        }
+    };
+    let submit = save.clone();
+    s.add_layer(
+        Dialog::new()
+            .title(format!("Value for {}", utils::display_date(date)))
+            .content(
+                LinearLayout::vertical()
+                    .child(
+                        EditView::new()
+                            .content(value)
+                            .on_submit(save)
+                            .with_name("Value")
+                            .fixed_width(CONFIGURATION.view_width),
+                    )
+                    .child(TextView::new("").with_name("ValueError")),
+            )
+            .button("Ok", move |s| {
+                let text = s
+                    .call_on_name("Value", |v: &mut EditView| v.get_content())
+                    .unwrap();
+                submit(s, &text);
+            })
+            .dismiss_button("Cancel"),
+    );
+}
+
+// ask before `habits` and their `days` of history are removed
+pub fn open_delete_window(s: &mut Cursive, habits: Vec<String>, days: usize) {
+    let message = match habits.as_slice() {
+        [habit] => format!("Delete `{}` and {} tracked days?", habit, days),
+        _ => format!(
+            "Delete {} habits ({}) and {} tracked days?",
+            habits.len(),
+            habits.join(", "),
+            days
+        ),
+    };
+    s.add_layer(
+        Dialog::around(TextView::new(message))
+            .title("Delete habit")
+            .button("Delete", move |s| {
+                s.call_on_name("Main", |app: &mut App| app.delete_habits(&habits));
+                s.pop_layer();
+            })
+            .dismiss_button("Cancel"),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clearing_a_day_removes_its_entry() {
+        let d = utils::today();
+        let mut c = Count::new("water", 3, false);
+        c.insert_entry(d, 0);
+        assert_eq!(c.get_by_date(d), Some(&0));
+        ShadowView::on_event(&mut c, Event::Char('x'));
+        assert_eq!(c.get_by_date(d), None);
+
+        let mut b = Bit::new("walk", false);
+        b.insert_entry(d, true.into());
+        ShadowView::on_event(&mut b, Event::Char('x'));
+        assert!(b.get_by_date(d).is_none());
+    }
+
+    #[test]
+    fn negative_days_do_not_overflow_the_week() {
+        let week = (1..=7)
+            .map(|d| NaiveDate::from_ymd(2026, 6, d))
+            .collect::<Vec<_>>();
+        let mut c = Count::new("balance", 3, false);
+        Habit::set_allow_negative(&mut c, true);
+        c.insert_entry(week[2], -1);
+        assert_eq!(Habit::remaining(&c, week[2]), 4);
+        assert_eq!(week_completions(&c, &week), (0, 21));
+        c.insert_entry(week[4], 3);
+        c.insert_entry(week[5], 2);
+        assert_eq!(week_completions(&c, &week), (5, 21));
+    }
+
+    #[test]
+    fn month_percent_rounds_the_completion_rate() {
+        let date = |m, d| NaiveDate::from_ymd(2026, m, d);
+        let mut c = Count::new("water", 3, false);
+        Habit::set_created(&mut c, date(2, 1));
+        for d in 1..=10 {
+            c.insert_entry(date(2, d), 3);
+        }
+        c.insert_entry(date(2, 11), 2);
+        let rate = |c: &Count, m| {
+            let first = date(m, 1);
+            Habit::checked_completion_rate(c, first, utils::last_day_of_month(first))
+        };
+        assert_eq!(percent_label(rate(&c, 2)), "36%");
+        assert_eq!(percent_label(rate(&c, 1)), "—");
+    }
+
+    #[test]
+    fn required_size_stays_within_tiny_constraints() {
+        let mut c = Count::new("water", 3, false);
+        for mode in &[
+            ViewMode::Day,
+            ViewMode::Month,
+            ViewMode::Year,
+            ViewMode::Recent(14),
+        ] {
+            Habit::set_view_mode(&mut c, *mode);
+            for &(x, y) in &[(0, 0), (1, 1), (3, 2), (2, 40), (40, 2)] {
+                let size = ShadowView::required_size(&mut c, Vec2::new(x, y));
+                assert!(size.x <= x && size.y <= y, "{:?} in {}x{}", mode, x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn today_mark_follows_the_goal_and_glyphs() {
+        assert_eq!(today_mark(Some(true), None, None), '✓');
+        assert_eq!(today_mark(Some(false), None, None), '✗');
+        assert_eq!(today_mark(None, None, None), '–');
+        assert_eq!(today_mark(Some(true), Some('●'), Some('○')), '●');
+        assert_eq!(today_mark(Some(false), Some('●'), Some('○')), '○');
+        assert_eq!(today_mark(None, Some('●'), Some('○')), '–');
+    }
+}